@@ -0,0 +1,90 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use benches::{document, Random};
+use shared::render::html::to_html;
+
+/// Document sizes (in bytes) to run the benchmarks on.
+const SIZES: [usize; 4] = [1_000, 100_000, 1_000_000, 10_000_000];
+
+/// Count of edits per iteration for benchmarks mutating a document.
+const EDITS: usize = 100;
+
+fn typing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("typing");
+    group.sample_size(10);
+
+    for size in SIZES.iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(size), size, |b, &size| {
+            b.iter_batched_ref(|| (document(size), Random::new(1)), |(tree, random)| {
+                for _ in 0..EDITS {
+                    let idx = random.next(tree.length() + 1);
+                    tree.insert(idx, 'x');
+                }
+            }, BatchSize::LargeInput);
+        });
+    }
+
+    group.finish();
+}
+
+fn formatting(c: &mut Criterion) {
+    let mut group = c.benchmark_group("formatting");
+    group.sample_size(10);
+
+    for size in SIZES.iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(size), size, |b, &size| {
+            b.iter_batched_ref(|| (document(size), Random::new(2)), |(tree, random)| {
+                for _ in 0..EDITS {
+                    let start = random.next(size - 16);
+                    let end = start + 1 + random.next(16);
+                    tree.set(start, end, random.style());
+                }
+            }, BatchSize::LargeInput);
+        });
+    }
+
+    group.finish();
+}
+
+fn deleting(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deleting");
+    group.sample_size(10);
+
+    for size in SIZES.iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(size), size, |b, &size| {
+            b.iter_batched_ref(|| document(size), |tree| tree.remove(size / 4, size / 2), BatchSize::LargeInput);
+        });
+    }
+
+    group.finish();
+}
+
+fn iterating(c: &mut Criterion) {
+    let mut group = c.benchmark_group("leaf_iter");
+    group.sample_size(10);
+
+    for size in SIZES.iter() {
+        let tree = document(*size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), size, |b, _| {
+            b.iter(|| tree.leaf_iter().count());
+        });
+    }
+
+    group.finish();
+}
+
+fn rendering(c: &mut Criterion) {
+    let mut group = c.benchmark_group("to_html");
+    group.sample_size(10);
+
+    for size in SIZES.iter() {
+        let tree = document(*size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), size, |b, _| {
+            b.iter(|| to_html(&tree));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, typing, formatting, deleting, iterating, rendering);
+criterion_main!(benches);
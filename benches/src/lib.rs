@@ -0,0 +1,59 @@
+use syntax_tree::Tree;
+use shared::info::FontStyle;
+
+const WORDS: [&str; 8] = ["lorem", "ipsum", "dolor", "sit", "amet", "consectetur", "adipiscing", "elit"];
+
+const STYLES: [FontStyle; 3] = [FontStyle::Bold, FontStyle::Italic, FontStyle::Underline];
+
+/// Maximum count of formatted ranges in a generated document.
+const MAX_FORMATTED_RANGES: usize = 1000;
+
+/// Simple deterministic pseudo random number generator (linear congruential generator).
+pub struct Random(u64);
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        Random(seed)
+    }
+
+    /// Get the next pseudo random number less than the passed bound.
+    pub fn next(&mut self, bound: usize) -> usize {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % bound
+    }
+
+    /// Get the next pseudo random font style.
+    pub fn style(&mut self) -> FontStyle {
+        STYLES[self.next(STYLES.len())]
+    }
+}
+
+/// Generate an ASCII text of the passed length (in bytes).
+pub fn text(length: usize) -> String {
+    let mut random = Random::new(42);
+
+    let mut result = String::with_capacity(length + 16);
+    while result.len() < length {
+        result.push_str(WORDS[random.next(WORDS.len())]);
+        result.push(' ');
+    }
+    result.truncate(length);
+    result
+}
+
+/// Generate a document of the passed length (in bytes) with formatted ranges spread over the text.
+pub fn document(length: usize) -> Tree<FontStyle> {
+    let mut random = Random::new(7);
+    let mut tree = Tree::new(&text(length), None);
+
+    let count = (length / 100).min(MAX_FORMATTED_RANGES);
+    for i in 0..count {
+        let start = i * length / count + random.next(16);
+        let end = (start + 1 + random.next(64)).min(length);
+        if start < end {
+            tree.set(start, end, random.style());
+        }
+    }
+
+    tree
+}
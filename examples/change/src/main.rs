@@ -10,6 +10,12 @@ fn main() {
             Event::NodeRemoved { parent, removed_idx } => println!(">>> Node with index {} has been removed under {}", removed_idx, parent.id()),
             Event::InfosChanged { node } => println!(">>> Nodes ({}) infos have been changed to '{:?}'", node.id(), node.infos()),
             Event::TextChanged { node } => println!(">>> Nodes ({}) text has been changed to '{}'", node.id(), node.text()),
+            Event::TextInserted { idx, text } => println!(">>> Text '{}' has been inserted at {}", text, idx),
+            Event::TextRemoved { idx, len } => println!(">>> {} characters have been removed at {}", len, idx),
+            Event::TextReplaced { start, end, text } => println!(">>> Range {}..{} has been replaced by '{}'", start, end, text),
+            Event::TextMoved { start, end, dest } => println!(">>> Range {}..{} has been moved to {}", start, end, dest),
+            Event::InfoApplied { start, end, info } => println!(">>> Info '{:?}' has been applied to range {}..{}", info, start, end),
+            Event::InfoRemoved { start, end, info } => println!(">>> Info '{:?}' has been removed from range {}..{}", info, start, end),
         }
     })));
     println!("{:#?}", tree);
@@ -0,0 +1,51 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use shared::info::FontStyle;
+use shared::model::{self, Operation};
+
+/// Maximum count of operations to run per input.
+const MAX_OPERATIONS: usize = 64;
+
+fn style(u: &mut Unstructured) -> Result<FontStyle> {
+    Ok(*u.choose(&[FontStyle::Bold, FontStyle::Italic, FontStyle::Underline])?)
+}
+
+fn text(u: &mut Unstructured) -> Result<String> {
+    let length = u.int_in_range(0..=5)?;
+
+    let mut result = String::new();
+    for _ in 0..length {
+        result.push(*u.choose(&['a', 'b', 'x', ' ', 'ä'])?);
+    }
+    Ok(result)
+}
+
+fn index(u: &mut Unstructured) -> Result<usize> {
+    Ok(u.arbitrary::<u8>()? as usize)
+}
+
+fn operation(u: &mut Unstructured) -> Result<Operation> {
+    Ok(match u.int_in_range(0..=5)? {
+        0 => Operation::Set { start: index(u)?, end: index(u)?, style: style(u)? },
+        1 => Operation::Unset { start: index(u)?, end: index(u)?, style: style(u)? },
+        2 => Operation::InsertStr { idx: index(u)?, text: text(u)? },
+        3 => Operation::Remove { idx: index(u)?, count: index(u)? },
+        4 => Operation::PushStr { text: text(u)? },
+        _ => Operation::Clear { keep_formats: u.arbitrary()? },
+    })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut u = Unstructured::new(data);
+
+    let mut operations = Vec::new();
+    while !u.is_empty() && operations.len() < MAX_OPERATIONS {
+        match operation(&mut u) {
+            Ok(operation) => operations.push(operation),
+            Err(_) => break,
+        }
+    }
+
+    model::run(&operations);
+});
@@ -1,2 +1,3 @@
 pub mod info;
+pub mod model;
 pub mod render;
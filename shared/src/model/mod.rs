@@ -0,0 +1,65 @@
+mod operation;
+mod reference;
+
+pub use operation::Operation;
+pub use reference::Reference;
+
+use syntax_tree::{Tree, Node};
+use crate::info::FontStyle;
+
+/// Apply the passed operations to a tree and the reference model,
+/// panicking as soon as the effective spans of both differ or the tree gets inconsistent.
+pub fn run(operations: &[Operation]) {
+    let mut tree: Tree<FontStyle> = Tree::new("", None);
+    let mut reference = Reference::new();
+
+    for (i, operation) in operations.iter().enumerate() {
+        if let Some(operation) = operation.resolve(reference.text()) {
+            operation.apply_to(&mut tree);
+            reference.apply(&operation);
+
+            assert_eq!(
+                spans_of(&tree), reference.spans(),
+                "Spans differ after operation {} ({:?}) of {:?}\n{:#?}", i, operation, operations, tree
+            );
+            assert_eq!(tree.validate(), vec!(), "Invalid tree after operation {} ({:?}) of {:?}\n{:#?}", i, operation, operations, tree);
+        }
+    }
+}
+
+/// Get the effective spans of the passed tree with adjacent spans of the same styles merged.
+pub fn spans_of(tree: &Tree<FontStyle>) -> Vec<(String, Vec<FontStyle>)> {
+    let mut result = Vec::new();
+    collect_spans(tree.get_root(), &[], &mut result);
+    result
+}
+
+fn collect_spans(node: &Node<FontStyle>, inherited: &[FontStyle], result: &mut Vec<(String, Vec<FontStyle>)>) {
+    let mut styles = inherited.to_vec();
+    for style in node.infos() {
+        if !styles.contains(style) {
+            styles.push(**style);
+        }
+    }
+
+    if node.is_leaf() {
+        styles.sort();
+        push_span(result, &node.text(), styles);
+    } else {
+        for child in node.children() {
+            collect_spans(child, &styles, result);
+        }
+    }
+}
+
+/// Push a span of text merging it with the last span if it has the same styles.
+fn push_span(spans: &mut Vec<(String, Vec<FontStyle>)>, text: &str, styles: Vec<FontStyle>) {
+    if text.is_empty() {
+        return;
+    }
+
+    match spans.last_mut() {
+        Some((t, s)) if *s == styles => t.push_str(text),
+        _ => spans.push((String::from(text), styles)),
+    }
+}
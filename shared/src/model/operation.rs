@@ -0,0 +1,69 @@
+use syntax_tree::Tree;
+use crate::info::FontStyle;
+
+/// Operation on a tree.
+/// Indices are arbitrary and need to be resolved against the current text before applying the operation.
+#[derive(Clone, Debug)]
+pub enum Operation {
+    Set { start: usize, end: usize, style: FontStyle },
+    Unset { start: usize, end: usize, style: FontStyle },
+    InsertStr { idx: usize, text: String },
+    Remove { idx: usize, count: usize },
+    PushStr { text: String },
+    Clear { keep_formats: bool },
+}
+
+impl Operation {
+    /// Resolve the indices of the operation to valid char boundaries of the passed text.
+    /// Returns none if the operation cannot be applied to the text.
+    pub fn resolve(&self, text: &str) -> Option<Operation> {
+        let length = text.len();
+
+        match self {
+            Operation::Set { start, end, style } | Operation::Unset { start, end, style } => {
+                let a = floor_boundary(text, start % (length + 1));
+                let b = floor_boundary(text, end % (length + 1));
+                if a == b {
+                    return None;
+                }
+
+                let (start, end) = if a < b { (a, b) } else { (b, a) };
+                Some(match self {
+                    Operation::Set { .. } => Operation::Set { start, end, style: *style },
+                    _ => Operation::Unset { start, end, style: *style },
+                })
+            }
+            Operation::InsertStr { idx, text: string } => Some(Operation::InsertStr {
+                idx: floor_boundary(text, idx % (length + 1)),
+                text: string.clone(),
+            }),
+            Operation::Remove { idx, count } => {
+                let idx = floor_boundary(text, idx % (length + 1));
+                let end = floor_boundary(text, idx + count % (length - idx + 1));
+
+                Some(Operation::Remove { idx, count: end - idx })
+            }
+            Operation::PushStr { .. } | Operation::Clear { .. } => Some(self.clone()),
+        }
+    }
+
+    /// Apply the (resolved) operation to the passed tree.
+    pub fn apply_to(&self, tree: &mut Tree<FontStyle>) {
+        match self {
+            Operation::Set { start, end, style } => tree.set(*start, *end, *style),
+            Operation::Unset { start, end, style } => tree.unset(*start, *end, *style),
+            Operation::InsertStr { idx, text } => tree.insert_str(*idx, text),
+            Operation::Remove { idx, count } => tree.remove(*idx, *count),
+            Operation::PushStr { text } => tree.push_str(text),
+            Operation::Clear { keep_formats } => tree.clear(*keep_formats),
+        }
+    }
+}
+
+/// Get the greatest char boundary of the text less than or equal to the passed index.
+fn floor_boundary(text: &str, mut idx: usize) -> usize {
+    while !text.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
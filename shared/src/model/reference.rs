@@ -0,0 +1,99 @@
+use std::collections::BTreeSet;
+use crate::info::FontStyle;
+use super::Operation;
+
+/// Naive reference model of a tree storing the styles of every byte of the text.
+/// Styles common to the whole text are kept when the text is removed and apply to text inserted afterwards.
+pub struct Reference {
+    text: String,
+    styles: Vec<BTreeSet<FontStyle>>,
+    root_styles: BTreeSet<FontStyle>,
+}
+
+impl Reference {
+    /// Create new empty reference model.
+    pub fn new() -> Reference {
+        Reference {
+            text: String::new(),
+            styles: Vec::new(),
+            root_styles: BTreeSet::new(),
+        }
+    }
+
+    /// Get the text of the model.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Apply the passed (resolved) operation.
+    pub fn apply(&mut self, operation: &Operation) {
+        match operation {
+            Operation::Set { start, end, style } => {
+                for styles in &mut self.styles[*start..*end] {
+                    styles.insert(*style);
+                }
+            }
+            Operation::Unset { start, end, style } => {
+                for styles in &mut self.styles[*start..*end] {
+                    styles.remove(style);
+                }
+            }
+            Operation::InsertStr { idx, text } => self.insert_str(*idx, text),
+            Operation::PushStr { text } => self.insert_str(self.text.len(), text),
+            Operation::Remove { idx, count } => self.remove(*idx, *count),
+            Operation::Clear { keep_formats } => {
+                self.remove(0, self.text.len());
+                if !keep_formats {
+                    self.root_styles.clear();
+                }
+            }
+        }
+    }
+
+    /// Get the spans of text with the same styles.
+    pub fn spans(&self) -> Vec<(String, Vec<FontStyle>)> {
+        let mut result: Vec<(String, Vec<FontStyle>)> = Vec::new();
+        for (i, ch) in self.text.char_indices() {
+            let styles: Vec<FontStyle> = self.styles[i].iter().cloned().collect();
+
+            match result.last_mut() {
+                Some((t, s)) if *s == styles => t.push(ch),
+                _ => result.push((ch.to_string(), styles)),
+            }
+        }
+        result
+    }
+
+    /// Get the styles applying to the whole text.
+    fn whole_text_styles(&self) -> BTreeSet<FontStyle> {
+        match self.styles.split_first() {
+            Some((first, rest)) => first.iter()
+                .filter(|style| rest.iter().all(|styles| styles.contains(style)))
+                .cloned()
+                .collect(),
+            None => self.root_styles.clone(),
+        }
+    }
+
+    /// Insert text carrying the styles of the whole text and the styles of the preceding character.
+    fn insert_str(&mut self, idx: usize, text: &str) {
+        let mut styles = self.whole_text_styles();
+        if idx > 0 {
+            styles.extend(self.styles[idx - 1].iter().cloned());
+        }
+
+        self.text.insert_str(idx, text);
+        for i in 0..text.len() {
+            self.styles.insert(idx + i, styles.clone());
+        }
+    }
+
+    fn remove(&mut self, idx: usize, count: usize) {
+        if count > 0 && count == self.text.len() {
+            self.root_styles = self.whole_text_styles();
+        }
+
+        self.text.replace_range(idx..idx + count, "");
+        self.styles.drain(idx..idx + count);
+    }
+}
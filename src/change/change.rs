@@ -16,4 +16,49 @@ pub enum Event<'a, T> {
     TextChanged {
         node: &'a Node<T>,
     },
+    /// Text has been inserted at the index.
+    /// Followed by `InfoApplied` events for the infos the inserted text carries.
+    TextInserted {
+        idx: usize,
+        text: &'a str,
+    },
+    /// A count of characters has been removed starting at the index.
+    TextRemoved {
+        idx: usize,
+        len: usize,
+    },
+    /// The text in the range (start inclusive, end exclusive) has been replaced by the text.
+    /// Followed by `InfoApplied` events for the infos the new text carries.
+    TextReplaced {
+        start: usize,
+        end: usize,
+        text: &'a str,
+    },
+    /// The text in the range (start inclusive, end exclusive) has been moved along with its infos
+    /// to the destination index (referring to the text before moving).
+    TextMoved {
+        start: usize,
+        end: usize,
+        dest: usize,
+    },
+    /// The info has been applied to the range (start inclusive, end exclusive).
+    InfoApplied {
+        start: usize,
+        end: usize,
+        info: &'a T,
+    },
+    /// The info has been removed from the range (start inclusive, end exclusive).
+    InfoRemoved {
+        start: usize,
+        end: usize,
+        info: &'a T,
+    },
+}
+
+impl<'a, T> Clone for Event<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
 }
+
+impl<'a, T> Copy for Event<'a, T> {}
@@ -0,0 +1,21 @@
+use crate::change::{Listener, OwnedEvent};
+use std::hash::Hash;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// Create a listener forwarding owned change events into a new channel.
+/// Returns the listener to subscribe to a tree and the receiving end of the channel.
+pub fn channel<T>() -> (Listener<T>, Receiver<OwnedEvent<T>>)
+    where T: Eq + Hash + Clone + Send + 'static {
+    let (sender, receiver) = mpsc::channel();
+
+    (forward(sender), receiver)
+}
+
+/// Create a listener forwarding owned change events to the passed sender.
+/// Events are dropped silently once the receiving end has been disconnected.
+pub fn forward<T>(sender: Sender<OwnedEvent<T>>) -> Listener<T>
+    where T: Eq + Hash + Clone + Send + 'static {
+    Box::new(move |event| {
+        let _ = sender.send(OwnedEvent::from(event));
+    })
+}
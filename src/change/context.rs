@@ -0,0 +1,46 @@
+use crate::change::{Event, Subscriptions};
+use crate::id::{self, NodeId};
+
+/// Context passed down to nodes while changing a tree.
+/// Nodes report change events through it instead of holding listeners themselves.
+pub(crate) struct Context<'a, T> {
+    /// Listeners subscribed to change events of the tree (if any).
+    subscriptions: Option<&'a Subscriptions<T>>,
+
+    /// Generator of the IDs for new nodes of the tree (if any).
+    ids: Option<&'a mut dyn id::Generator>,
+}
+
+impl<'a, T> Context<'a, T> {
+    /// Create new context emitting events to the passed subscriptions
+    /// and taking IDs for new nodes from the passed generator.
+    pub fn new(subscriptions: &'a Subscriptions<T>, ids: &'a mut dyn id::Generator) -> Context<'a, T> {
+        Context {
+            subscriptions: Some(subscriptions),
+            ids: Some(ids),
+        }
+    }
+
+    /// Create new context for nodes not belonging to a tree.
+    pub fn detached() -> Context<'a, T> {
+        Context {
+            subscriptions: None,
+            ids: None,
+        }
+    }
+
+    /// Emit a change event.
+    pub fn emit(&self, event: Event<T>) {
+        if let Some(s) = self.subscriptions {
+            s.emit(event);
+        }
+    }
+
+    /// Get the ID for the next node created.
+    pub fn next_id(&mut self) -> NodeId {
+        match self.ids.as_mut() {
+            Some(ids) => ids.next_id(),
+            None => id::next_detached(),
+        }
+    }
+}
@@ -1,4 +1,9 @@
 use crate::change::Event;
 
 /// Function called when an event is emitted by the tree synchronously.
+#[cfg(not(feature = "sync"))]
 pub type Listener<T> = Box<dyn Fn(Event<T>)>;
+
+/// Function called when an event is emitted by the tree synchronously.
+#[cfg(feature = "sync")]
+pub type Listener<T> = Box<dyn Fn(Event<T>) + Send + Sync>;
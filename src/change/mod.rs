@@ -1,5 +1,14 @@
 mod change;
+mod channel;
+mod context;
 mod listener;
+mod owned;
+mod subscription;
 
 pub use change::Event;
+pub use channel::{channel, forward};
 pub use listener::Listener;
+pub use owned::OwnedEvent;
+pub use subscription::SubscriptionId;
+pub(crate) use context::Context;
+pub(crate) use subscription::Subscriptions;
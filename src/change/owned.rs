@@ -0,0 +1,100 @@
+use crate::change::Event;
+use crate::id::NodeId;
+use std::hash::Hash;
+
+/// Change event owning its data so that it can outlive the change and be sent to other threads.
+/// Nodes are referred to by their IDs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OwnedEvent<T> {
+    NodeAdded {
+        parent_id: NodeId,
+        node_id: NodeId,
+        added_idx: usize,
+    },
+    NodeRemoved {
+        parent_id: NodeId,
+        removed_idx: usize,
+    },
+    InfosChanged {
+        node_id: NodeId,
+        infos: Vec<T>,
+    },
+    TextChanged {
+        node_id: NodeId,
+        text: String,
+    },
+    TextInserted {
+        idx: usize,
+        text: String,
+    },
+    TextRemoved {
+        idx: usize,
+        len: usize,
+    },
+    TextReplaced {
+        start: usize,
+        end: usize,
+        text: String,
+    },
+    TextMoved {
+        start: usize,
+        end: usize,
+        dest: usize,
+    },
+    InfoApplied {
+        start: usize,
+        end: usize,
+        info: T,
+    },
+    InfoRemoved {
+        start: usize,
+        end: usize,
+        info: T,
+    },
+}
+
+impl<'a, T> From<Event<'a, T>> for OwnedEvent<T>
+    where T: Eq + Hash + Clone {
+    fn from(event: Event<'a, T>) -> Self {
+        match event {
+            Event::NodeAdded { parent, added_idx } => OwnedEvent::NodeAdded {
+                parent_id: parent.id(),
+                node_id: parent.children()[added_idx].id(),
+                added_idx,
+            },
+            Event::NodeRemoved { parent, removed_idx } => OwnedEvent::NodeRemoved {
+                parent_id: parent.id(),
+                removed_idx,
+            },
+            Event::InfosChanged { node } => OwnedEvent::InfosChanged {
+                node_id: node.id(),
+                infos: node.infos().map(|i| T::clone(i)).collect(),
+            },
+            Event::TextChanged { node } => OwnedEvent::TextChanged {
+                node_id: node.id(),
+                text: node.text(),
+            },
+            Event::TextInserted { idx, text } => OwnedEvent::TextInserted {
+                idx,
+                text: String::from(text),
+            },
+            Event::TextRemoved { idx, len } => OwnedEvent::TextRemoved { idx, len },
+            Event::TextReplaced { start, end, text } => OwnedEvent::TextReplaced {
+                start,
+                end,
+                text: String::from(text),
+            },
+            Event::TextMoved { start, end, dest } => OwnedEvent::TextMoved { start, end, dest },
+            Event::InfoApplied { start, end, info } => OwnedEvent::InfoApplied {
+                start,
+                end,
+                info: info.clone(),
+            },
+            Event::InfoRemoved { start, end, info } => OwnedEvent::InfoRemoved {
+                start,
+                end,
+                info: info.clone(),
+            },
+        }
+    }
+}
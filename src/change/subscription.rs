@@ -0,0 +1,49 @@
+use crate::change::{Event, Listener};
+
+/// ID of a listener subscribed to the change events of a tree.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(usize);
+
+/// Listeners subscribed to the change events of a tree.
+pub(crate) struct Subscriptions<T> {
+    /// ID to give the next subscribed listener.
+    next_id: usize,
+
+    /// Subscribed listeners in order of subscription.
+    listeners: Vec<(SubscriptionId, Listener<T>)>,
+}
+
+impl<T> Subscriptions<T> {
+    /// Create new empty subscriptions.
+    pub fn new() -> Subscriptions<T> {
+        Subscriptions {
+            next_id: 0,
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Subscribe the passed listener.
+    pub fn subscribe(&mut self, listener: Listener<T>) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+
+        self.listeners.push((id, listener));
+        id
+    }
+
+    /// Unsubscribe the listener with the passed ID.
+    /// Returns whether a listener has been unsubscribed.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let length = self.listeners.len();
+        self.listeners.retain(|(i, _)| *i != id);
+
+        self.listeners.len() < length
+    }
+
+    /// Emit the passed event to all subscribed listeners.
+    pub fn emit(&self, event: Event<T>) {
+        for (_, listener) in &self.listeners {
+            listener(event);
+        }
+    }
+}
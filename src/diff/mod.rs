@@ -0,0 +1,29 @@
+mod operation;
+
+pub use operation::Operation;
+
+/// Get the length of the common prefix and the length of the common suffix of the passed strings.
+/// Prefix and suffix do not overlap and end on char boundaries.
+pub(crate) fn common_affixes(a: &str, b: &str) -> (usize, usize) {
+    let a_bytes = a.as_bytes();
+    let b_bytes = b.as_bytes();
+    let max = if a.len() < b.len() { a.len() } else { b.len() };
+
+    let mut prefix = 0;
+    while prefix < max && a_bytes[prefix] == b_bytes[prefix] {
+        prefix += 1;
+    }
+    while !a.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+
+    let mut suffix = 0;
+    while suffix < max - prefix && a_bytes[a.len() - suffix - 1] == b_bytes[b.len() - suffix - 1] {
+        suffix += 1;
+    }
+    while !a.is_char_boundary(a.len() - suffix) || !b.is_char_boundary(b.len() - suffix) {
+        suffix -= 1;
+    }
+
+    (prefix, suffix)
+}
@@ -0,0 +1,39 @@
+/// Operation changing the text or formatting of a tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Operation<T> {
+    /// Insert text carrying no syntax/format infos at the index.
+    Insert {
+        idx: usize,
+        text: String,
+    },
+    /// Delete a count of characters starting at the index.
+    Delete {
+        idx: usize,
+        len: usize,
+    },
+    /// Set the info for the range (start inclusive, end exclusive).
+    Set {
+        start: usize,
+        end: usize,
+        info: T,
+    },
+    /// Unset the info for the range (start inclusive, end exclusive).
+    Unset {
+        start: usize,
+        end: usize,
+        info: T,
+    },
+}
+
+impl<T> Operation<T> {
+    /// Transform the info of the operation (if any) with the passed function.
+    pub(crate) fn map_info<U, F>(self, f: F) -> Operation<U>
+        where F: FnOnce(T) -> U {
+        match self {
+            Operation::Insert { idx, text } => Operation::Insert { idx, text },
+            Operation::Delete { idx, len } => Operation::Delete { idx, len },
+            Operation::Set { start, end, info } => Operation::Set { start, end, info: f(info) },
+            Operation::Unset { start, end, info } => Operation::Unset { start, end, info: f(info) },
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::id::NodeId;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Generator of the IDs for the nodes created in a tree.
+pub trait Generator {
+    /// Get the ID for the next node created.
+    fn next_id(&mut self) -> NodeId;
+}
+
+/// Generator owned by a tree.
+#[cfg(not(feature = "sync"))]
+pub type BoxedGenerator = Box<dyn Generator>;
+
+/// Generator owned by a tree.
+#[cfg(feature = "sync")]
+pub type BoxedGenerator = Box<dyn Generator + Send + Sync>;
+
+/// Generator counting up from zero.
+/// Used by trees unless another generator is set.
+#[derive(Debug, Default)]
+pub struct Sequential {
+    next: u128,
+}
+
+impl Sequential {
+    /// Create new generator starting at zero.
+    pub fn new() -> Sequential {
+        Sequential {
+            next: 0,
+        }
+    }
+}
+
+impl Generator for Sequential {
+    fn next_id(&mut self) -> NodeId {
+        let id = NodeId::new(self.next);
+        self.next += 1;
+        id
+    }
+}
+
+/// Generator using random (version 4) UUIDs.
+#[cfg(feature = "uuid")]
+#[derive(Debug, Default)]
+pub struct RandomUuid;
+
+#[cfg(feature = "uuid")]
+impl Generator for RandomUuid {
+    fn next_id(&mut self) -> NodeId {
+        NodeId::new(uuid::Uuid::new_v4().as_u128())
+    }
+}
+
+/// Counter for nodes created outside of a tree.
+static NEXT_DETACHED: AtomicU64 = AtomicU64::new(0);
+
+/// Bit set in the IDs of nodes created outside of a tree to keep them apart from those counted by `Sequential`.
+const DETACHED_BIT: u128 = 1 << 127;
+
+/// Get the ID for the next node created outside of a tree.
+pub(crate) fn next_detached() -> NodeId {
+    NodeId::new(DETACHED_BIT | NEXT_DETACHED.fetch_add(1, Ordering::Relaxed) as u128)
+}
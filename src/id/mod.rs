@@ -0,0 +1,8 @@
+mod node_id;
+mod generator;
+
+pub use node_id::NodeId;
+pub use generator::{Generator, BoxedGenerator, Sequential};
+#[cfg(feature = "uuid")]
+pub use generator::RandomUuid;
+pub(crate) use generator::next_detached;
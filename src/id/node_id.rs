@@ -0,0 +1,26 @@
+use std::fmt;
+
+/// ID uniquely identifying a node within its tree.
+/// IDs are not unique across trees since every tree numbers its nodes on its own,
+/// and cloned nodes (as well as nodes shared with snapshots) keep the ID of the original.
+/// Nodes created outside of a tree get IDs with the highest bit set.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(u128);
+
+impl NodeId {
+    /// Create new ID from the passed value.
+    pub fn new(value: u128) -> NodeId {
+        NodeId(value)
+    }
+
+    /// Get the value of the ID.
+    pub fn value(&self) -> u128 {
+        self.0
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,24 @@
+/// Behavior of a syntax/format info when text is inserted at the boundary of a range carrying it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Expand {
+    /// Text inserted directly before the range will carry the info as well.
+    InclusiveStart,
+    /// Text inserted directly after the range will carry the info as well.
+    InclusiveEnd,
+    /// Text inserted at either boundary of the range will not carry the info.
+    Exclusive,
+}
+
+impl Default for Expand {
+    fn default() -> Self {
+        Expand::InclusiveEnd
+    }
+}
+
+/// Function deciding how the passed syntax/format info expands when inserting text.
+#[cfg(not(feature = "sync"))]
+pub type ExpandBehavior<T> = Box<dyn Fn(&T) -> Expand>;
+
+/// Function deciding how the passed syntax/format info expands when inserting text.
+#[cfg(feature = "sync")]
+pub type ExpandBehavior<T> = Box<dyn Fn(&T) -> Expand + Send + Sync>;
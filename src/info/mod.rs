@@ -0,0 +1,5 @@
+mod expand;
+mod shared;
+
+pub use expand::{Expand, ExpandBehavior};
+pub use shared::Shared;
@@ -0,0 +1,7 @@
+/// Reference counted pointer sharing syntax/format infos and nodes.
+#[cfg(not(feature = "sync"))]
+pub type Shared<T> = std::rc::Rc<T>;
+
+/// Reference counted pointer sharing syntax/format infos and nodes between threads.
+#[cfg(feature = "sync")]
+pub type Shared<T> = std::sync::Arc<T>;
@@ -0,0 +1,37 @@
+use crate::Node;
+use crate::iterator;
+use std::hash::Hash;
+
+/// Iterator over the ancestors of a node from its parent up to the root.
+pub struct Ancestors<'a, T> {
+    items: Vec<iterator::Item<'a, T>>,
+}
+
+impl<'a, T> Ancestors<'a, T>
+    where T: Eq + Hash {
+    /// Create the iterator for the node reached by descending the passed path from the passed root.
+    pub fn new(root: &'a Node<T>, path: &[usize]) -> Self {
+        let mut items = Vec::with_capacity(path.len());
+
+        let mut item = iterator::Item::root(root);
+        for &idx in path {
+            let start = item.start + item.node.children()[..idx].iter().map(|c| c.length()).sum::<usize>();
+            let child = item.child(&item.shared_path(), idx, start);
+            items.push(item);
+            item = child;
+        }
+
+        Ancestors {
+            items,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Ancestors<'a, T>
+    where T: Eq + Hash {
+    type Item = iterator::Item<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.items.pop()
+    }
+}
@@ -0,0 +1,42 @@
+use crate::Node;
+use crate::iterator;
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+/// Breadth-first iterator (level by level, from left to right).
+pub struct BreadthFirst<'a, T> {
+    queue: VecDeque<iterator::Item<'a, T>>,
+}
+
+impl<'a, T> BreadthFirst<'a, T>
+    where T: Eq + Hash {
+    pub fn new(root: &'a Node<T>) -> Self {
+        let mut queue = VecDeque::new();
+        queue.push_back(iterator::Item::root(root));
+
+        BreadthFirst {
+            queue,
+        }
+    }
+}
+
+impl<'a, T> Iterator for BreadthFirst<'a, T>
+    where T: Eq + Hash {
+    type Item = iterator::Item<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.queue.pop_front()?;
+
+        if !item.node.is_leaf() {
+            let path = item.shared_path();
+            let mut start = item.start;
+            for i in 0..item.node.child_count() {
+                let child = item.child(&path, i, start);
+                start = child.end;
+                self.queue.push_back(child);
+            }
+        }
+
+        Some(item)
+    }
+}
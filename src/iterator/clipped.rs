@@ -0,0 +1,27 @@
+use crate::iterator::Item;
+use std::hash::Hash;
+
+/// Item iterated over using a range iterator along with the part of its text inside the range.
+pub struct Clipped<'a, T> {
+    /// Item of the node (with the start and end offsets of the whole node).
+    pub item: Item<'a, T>,
+
+    /// Absolute start offset of the part of the nodes text inside the range (inclusive).
+    pub start: usize,
+
+    /// Absolute end offset of the part of the nodes text inside the range (exclusive).
+    pub end: usize,
+}
+
+impl<'a, T> Clipped<'a, T>
+    where T: Eq + Hash {
+    /// Get the part of the nodes text inside the range.
+    pub fn text(&self) -> String {
+        let text = self.item.node.text();
+        if self.start == self.item.start && self.end == self.item.end {
+            text
+        } else {
+            text[self.start - self.item.start..self.end - self.item.start].to_string()
+        }
+    }
+}
@@ -1,7 +1,52 @@
 use crate::Node;
+use crate::info::Shared;
+use crate::iterator::Path;
+use std::hash::Hash;
 
 /// Item iterated over using an iterator.
 pub struct Item<'a, T> {
     pub node: &'a Node<T>,
     pub level: usize,
+
+    /// Absolute start offset of the nodes text (inclusive).
+    pub start: usize,
+
+    /// Absolute end offset of the nodes text (exclusive).
+    pub end: usize,
+
+    /// Indices of the children to descend to from the root to reach the node.
+    pub path: Path,
+}
+
+impl<'a, T> Item<'a, T>
+    where T: Eq + Hash {
+    /// Create the item for the passed root node.
+    pub(crate) fn root(node: &'a Node<T>) -> Self {
+        Item {
+            node,
+            level: 0,
+            start: 0,
+            end: node.length(),
+            path: Path::root(),
+        }
+    }
+
+    /// Get the path of the item to share between the items of the nodes children.
+    pub(crate) fn shared_path(&self) -> Shared<Path> {
+        Shared::new(self.path.clone())
+    }
+
+    /// Create the item for the child at the passed index whose text starts at the passed offset.
+    /// The passed path is the shared path of this item (see `shared_path`).
+    pub(crate) fn child(&self, path: &Shared<Path>, idx: usize, start: usize) -> Self {
+        let node = &self.node.children()[idx];
+
+        Item {
+            node,
+            level: self.level + 1,
+            start,
+            end: start + node.length(),
+            path: Path::child(path, idx),
+        }
+    }
 }
@@ -1,5 +1,19 @@
 mod pre_order;
+mod post_order;
+mod reverse_pre_order;
+mod breadth_first;
+mod range;
+mod ancestors;
 mod item;
+mod clipped;
+mod path;
 
 pub use pre_order::PreOrder;
+pub use post_order::PostOrder;
+pub use reverse_pre_order::ReversePreOrder;
+pub use breadth_first::BreadthFirst;
+pub use range::Range;
+pub use ancestors::Ancestors;
 pub use item::Item;
+pub use clipped::Clipped;
+pub use path::Path;
@@ -0,0 +1,60 @@
+use crate::info::Shared;
+use std::fmt;
+
+/// Indices of the children to descend to from the root to reach a node.
+/// Siblings share the path of their parent, so the indices are only collected when asked for.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Path {
+    /// Path of the parent along with the index of the node in the parents children (none for the root).
+    parent: Option<(Shared<Path>, usize)>,
+}
+
+impl Path {
+    /// Get the path of the root.
+    pub fn root() -> Self {
+        Path {
+            parent: None,
+        }
+    }
+
+    /// Get the path of the child at the passed index of the node with the passed path.
+    pub fn child(parent: &Shared<Path>, idx: usize) -> Self {
+        Path {
+            parent: Some((Shared::clone(parent), idx)),
+        }
+    }
+
+    /// Get the count of indices in the path (the level of the node).
+    pub fn len(&self) -> usize {
+        let mut result = 0;
+        let mut path = self;
+        while let Some((parent, _)) = &path.parent {
+            result += 1;
+            path = parent;
+        }
+        result
+    }
+
+    /// Check whether this is the path of the root.
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_none()
+    }
+
+    /// Collect the indices from the root to the node.
+    pub fn to_vec(&self) -> Vec<usize> {
+        let mut result = Vec::new();
+        let mut path = self;
+        while let Some((parent, idx)) = &path.parent {
+            result.push(*idx);
+            path = parent;
+        }
+        result.reverse();
+        result
+    }
+}
+
+impl fmt::Debug for Path {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.to_vec()).finish()
+    }
+}
@@ -0,0 +1,45 @@
+use crate::Node;
+use crate::iterator;
+use crate::info::Shared;
+use std::hash::Hash;
+
+/// Item on the current path along with its path shared with its children (once visiting them)
+/// and the index and start offset of the next child to visit.
+type Entry<'a, T> = (iterator::Item<'a, T>, Option<Shared<iterator::Path>>, usize, usize);
+
+/// Depth-first iterator in post-order (children before their parent).
+pub struct PostOrder<'a, T> {
+    stack: Vec<Entry<'a, T>>,
+}
+
+impl<'a, T> PostOrder<'a, T>
+    where T: Eq + Hash {
+    pub fn new(root: &'a Node<T>) -> Self {
+        PostOrder {
+            stack: vec!((iterator::Item::root(root), None, 0, 0)),
+        }
+    }
+}
+
+impl<'a, T> Iterator for PostOrder<'a, T>
+    where T: Eq + Hash {
+    type Item = iterator::Item<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (item, path, next_child, next_start) = self.stack.last_mut()?;
+
+            if !item.node.is_leaf() && *next_child < item.node.child_count() {
+                let path = path.get_or_insert_with(|| item.shared_path());
+                let child = item.child(path, *next_child, *next_start);
+                *next_child += 1;
+                *next_start = child.end;
+
+                let start = child.start;
+                self.stack.push((child, None, 0, start));
+            } else {
+                return self.stack.pop().map(|(item, _, _, _)| item);
+            }
+        }
+    }
+}
@@ -11,10 +11,7 @@ impl<'a, T> PreOrder<'a, T>
     where T: Eq + Hash {
     pub fn new(root: &'a Node<T>) -> Self {
         PreOrder {
-            stack: vec!(iterator::Item {
-                node: root,
-                level: 0,
-            }),
+            stack: vec!(iterator::Item::root(root)),
         }
     }
 }
@@ -30,13 +27,16 @@ impl<'a, T> Iterator for PreOrder<'a, T>
             let item = self.stack.pop().unwrap();
 
             if !item.node.is_leaf() {
-                let length = item.node.child_count();
-                for i in 0..length {
-                    self.stack.push(iterator::Item {
-                        node: &item.node.children()[length - i - 1],
-                        level: item.level + 1,
-                    });
+                let mut children = Vec::with_capacity(item.node.child_count());
+                let path = item.shared_path();
+                let mut start = item.start;
+                for i in 0..item.node.child_count() {
+                    let child = item.child(&path, i, start);
+                    start = child.end;
+                    children.push(child);
                 }
+
+                self.stack.extend(children.into_iter().rev());
             }
 
             Some(item)
@@ -0,0 +1,63 @@
+use crate::Node;
+use crate::iterator;
+use std::hash::Hash;
+
+/// Depth-first iterator in pre-order over the nodes intersecting a range.
+/// Items are yielded along with the part of their text inside the range.
+pub struct Range<'a, T> {
+    stack: Vec<iterator::Item<'a, T>>,
+    start: usize,
+    end: usize,
+}
+
+impl<'a, T> Range<'a, T>
+    where T: Eq + Hash {
+    pub fn new(root: &'a Node<T>, start: usize, end: usize) -> Self {
+        let item = iterator::Item::root(root);
+
+        Range {
+            stack: if start < end && item.start < end && item.end > start { vec!(item) } else { Vec::new() },
+            start,
+            end,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Range<'a, T>
+    where T: Eq + Hash {
+    type Item = iterator::Clipped<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.stack.is_empty() {
+            None
+        } else {
+            let item = self.stack.pop().unwrap();
+
+            if !item.node.is_leaf() {
+                let mut children = Vec::new();
+                let path = item.shared_path();
+                let mut start = item.start;
+                for i in 0..item.node.child_count() {
+                    if start >= self.end {
+                        break;
+                    }
+
+                    let child = item.child(&path, i, start);
+                    start = child.end;
+                    if child.end > self.start {
+                        children.push(child);
+                    }
+                }
+
+                self.stack.extend(children.into_iter().rev());
+            }
+
+            let (start, end) = (item.start.max(self.start), item.end.min(self.end));
+            Some(iterator::Clipped {
+                item,
+                start,
+                end,
+            })
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::Node;
+use crate::iterator;
+use crate::info::Shared;
+use std::hash::Hash;
+
+/// Item on the current path along with its path shared with its children (once visiting them),
+/// the count of children not visited yet and the end offset of the next child to visit.
+type Entry<'a, T> = (iterator::Item<'a, T>, Option<Shared<iterator::Path>>, usize, usize);
+
+/// Depth-first iterator yielding the nodes in reverse pre-order
+/// (starting with the last node of the pre-order and ending with the root).
+pub struct ReversePreOrder<'a, T> {
+    stack: Vec<Entry<'a, T>>,
+}
+
+impl<'a, T> ReversePreOrder<'a, T>
+    where T: Eq + Hash {
+    pub fn new(root: &'a Node<T>) -> Self {
+        let root = iterator::Item::root(root);
+        let (child_count, end) = (root.node.child_count(), root.end);
+
+        ReversePreOrder {
+            stack: vec!((root, None, child_count, end)),
+        }
+    }
+}
+
+impl<'a, T> Iterator for ReversePreOrder<'a, T>
+    where T: Eq + Hash {
+    type Item = iterator::Item<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (item, path, remaining, next_end) = self.stack.last_mut()?;
+
+            if *remaining > 0 {
+                *remaining -= 1;
+                let path = path.get_or_insert_with(|| item.shared_path());
+                let length = item.node.children()[*remaining].length();
+                let child = item.child(path, *remaining, *next_end - length);
+                *next_end = child.start;
+
+                let (child_count, end) = (child.node.child_count(), child.end);
+                self.stack.push((child, None, child_count, end));
+            } else {
+                return self.stack.pop().map(|(item, _, _, _)| item);
+            }
+        }
+    }
+}
@@ -1,8 +1,16 @@
 pub mod iterator;
 pub mod change;
+pub mod info;
+pub mod diff;
+pub mod validation;
+pub mod visit;
+pub mod id;
 
 mod tree;
 mod node;
+mod snapshot;
+mod span;
 
 pub use tree::Tree;
 pub use node::Node;
+pub use snapshot::Snapshot;
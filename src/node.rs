@@ -1,30 +1,33 @@
 use std::fmt;
-use crate::{iterator, change};
-use std::rc::Rc;
+use crate::{iterator, change, validation, visit};
+use crate::id::NodeId;
+use crate::change::Context;
+use crate::info::Shared;
+use crate::span::{self, Span};
 use std::collections::HashSet;
 use std::collections::hash_set::Iter;
 use std::hash::Hash;
 use std::fmt::Debug;
-use uuid::Uuid;
 
 pub struct Node<T> {
-    /// ID uniquely identifying the node.
-    id: String,
+    /// ID uniquely identifying the node within its tree.
+    id: NodeId,
 
     /// Children of the node.
-    children: Option<Vec<Node<T>>>,
+    /// Each child is shared with snapshots until it is changed.
+    children: Option<Vec<Shared<Node<T>>>>,
 
     /// Set to be filled with syntax/format information.
-    infos: HashSet<Rc<T>>,
+    infos: HashSet<Shared<T>>,
 
     /// Text the node (when a leaf) is holding.
     text: Option<String>,
 
+    /// Length of the text under the node (kept up to date to avoid walking the subtree).
+    length: usize,
+
     /// Whether this node is the root node.
     root: bool,
-
-    /// Change event listener reference.
-    listener: Option<Rc<change::Listener<T>>>,
 }
 
 struct AffectedNode {
@@ -44,44 +47,63 @@ struct AffectedNode {
 impl<T> Node<T>
     where T: Eq + Hash {
     /// Create new leaf node.
+    /// Nodes created outside of a tree get their IDs from a process-wide counter.
     pub fn new_leaf(text: String) -> Node<T> {
+        Node::new_leaf_in(text, &mut Context::detached())
+    }
+
+    /// Create new leaf node taking its ID from the passed context.
+    pub(crate) fn new_leaf_in(text: String, ctx: &mut Context<T>) -> Node<T> {
         Node {
-            id: Uuid::new_v4().to_string(),
+            id: ctx.next_id(),
             children: None,
             infos: HashSet::new(),
+            length: text.len(),
             text: Some(text),
             root: false,
-            listener: None,
         }
     }
 
     /// Create new node.
+    /// Nodes created outside of a tree get their IDs from a process-wide counter.
     pub fn new() -> Node<T> {
+        Node::new_in(&mut Context::detached())
+    }
+
+    /// Create new node taking its ID from the passed context.
+    pub(crate) fn new_in(ctx: &mut Context<T>) -> Node<T> {
         Node {
-            id: Uuid::new_v4().to_string(),
+            id: ctx.next_id(),
             children: None,
             infos: HashSet::new(),
             text: None,
+            length: 0,
             root: false,
-            listener: None,
         }
     }
 
     /// Create new root node.
+    /// Nodes created outside of a tree get their IDs from a process-wide counter.
     pub fn new_root(string: &str) -> Node<T> {
+        Node::new_root_in(string, &mut Context::detached())
+    }
+
+    /// Create new root node taking its ID from the passed context.
+    pub(crate) fn new_root_in(string: &str, ctx: &mut Context<T>) -> Node<T> {
         Node {
-            id: Uuid::new_v4().to_string(),
+            id: ctx.next_id(),
             children: None,
             infos: HashSet::new(),
             text: Some(String::from(string)),
+            length: string.len(),
             root: true,
-            listener: None,
         }
     }
 
     /// Get the ID of the node.
-    pub fn id(&self) -> &String {
-        &self.id
+    /// The ID is only unique within the tree of the node (see `NodeId`).
+    pub fn id(&self) -> NodeId {
+        self.id
     }
 
     /// Check whether this node is a
@@ -94,12 +116,19 @@ impl<T> Node<T>
 
     /// Add a child to this node.
     pub fn add_child(&mut self, child: Node<T>) {
+        self.add_child_in(child, &mut Context::detached());
+    }
+
+    /// Add a child to this node emitting change events to the passed context.
+    pub(crate) fn add_child_in(&mut self, child: Node<T>, ctx: &mut Context<T>) {
         if self.children.is_none() {
             self.children = Some(Vec::new());
+            self.length = 0;
         }
 
-        self.children.as_mut().unwrap().push(child);
-        self.emit_event(change::Event::NodeAdded { parent: &self, added_idx: self.child_count() - 1 });
+        self.length += child.length;
+        self.children_mut().push(Shared::new(child));
+        ctx.emit(change::Event::NodeAdded { parent: &self, added_idx: self.child_count() - 1 });
     }
 
     /// Get text the node (or children) is/are holding.
@@ -114,36 +143,39 @@ impl<T> Node<T>
             }
         } else {
             let mut result = String::with_capacity(self.length());
-            for child in self.children.as_ref().unwrap() {
+            for child in self.children() {
                 result.push_str(&child.text());
             }
             result
         }
     }
 
+    /// Replace the text of this leaf node.
+    pub(crate) fn set_text(&mut self, text: String) {
+        self.length = text.len();
+        self.text = Some(text);
+    }
+
     /// Length of the underlying text.
     pub fn length(&self) -> usize {
-        if self.is_leaf() {
-            self.text.as_ref().unwrap().len()
-        } else {
-            let mut result = 0;
-            for child in self.children.as_ref().unwrap() {
-                result += child.length();
-            }
-            result
-        }
+        self.length
     }
 
     /// Get iterator over all infos this node has.
-    pub fn infos(&self) -> Iter<Rc<T>> {
+    pub fn infos(&self) -> Iter<Shared<T>> {
         self.infos.iter()
     }
 
     /// Add info to the node.
-    pub fn add_info(&mut self, info: Rc<T>) {
+    pub fn add_info(&mut self, info: Shared<T>) {
         self.infos.insert(info);
     }
 
+    /// Get the infos of this node for mutation.
+    pub(crate) fn infos_mut(&mut self) -> &mut HashSet<Shared<T>> {
+        &mut self.infos
+    }
+
     /// Clear all infos from this node without recursion in children.
     pub fn clear_infos(&mut self) {
         self.infos.clear();
@@ -153,12 +185,18 @@ impl<T> Node<T>
     /// Specify recurse whether the info should be removed from children as well.
     /// Since a node can end up useless without info, it might have to be replaced
     /// by its children which are then returned by this method.
-    pub fn remove_info(&mut self, start_idx: usize, end_idx: usize, info: Rc<T>, recurse: bool) -> Option<Vec<Node<T>>> {
+    pub fn remove_info(&mut self, start_idx: usize, end_idx: usize, info: Shared<T>, recurse: bool) -> Option<Vec<Node<T>>> {
+        self.remove_info_in(start_idx, end_idx, info, recurse, &mut Context::detached())
+    }
+
+    /// Remove info from the node emitting change events to the passed context.
+    /// See `remove_info` for details.
+    pub(crate) fn remove_info_in(&mut self, start_idx: usize, end_idx: usize, info: Shared<T>, recurse: bool, ctx: &mut Context<T>) -> Option<Vec<Node<T>>> {
         let mut set_later = Vec::new();
 
         if self.is_leaf() {
             if self.infos.remove(&info) {
-                self.emit_event(change::Event::InfosChanged { node: &self });
+                ctx.emit(change::Event::InfosChanged { node: &self });
 
                 let length = self.length();
 
@@ -177,26 +215,35 @@ impl<T> Node<T>
             }
         } else if recurse {
             if self.infos.remove(&info) {
-                self.emit_event(change::Event::InfosChanged { node: &self });
+                ctx.emit(change::Event::InfosChanged { node: &self });
+
+                // Keep the info for the parts of the node outside of the range
+                let length = self.length();
+                if start_idx > 0 {
+                    set_later.push((vec!((0, start_idx)), vec!(Shared::clone(&info))));
+                }
+                if end_idx < length {
+                    set_later.push((vec!((end_idx, length)), vec!(Shared::clone(&info))));
+                }
             }
 
             let mut offset = 0;
             let mut replace_later = Vec::new();
             for i in 0..self.child_count() {
-                let child = &mut self.children.as_mut().unwrap()[i];
-                let length = child.length();
+                let length = self.children()[i].length();
                 let ranges_intersect = offset < end_idx && start_idx < offset + length;
 
                 if ranges_intersect {
+                    let child = self.child_mut(i);
                     let start = if start_idx > offset { start_idx - offset } else { 0 };
                     let end = if end_idx - offset > length { length } else { end_idx - offset };
 
                     let mut old_infos = Vec::new();
                     for old_info in child.infos() {
-                        old_infos.push(Rc::clone(old_info));
+                        old_infos.push(Shared::clone(old_info));
                     }
 
-                    if let Some(v) = child.remove_info(start, end, Rc::clone(&info), recurse) {
+                    if let Some(v) = child.remove_info_in(start, end, Shared::clone(&info), recurse, ctx) {
                         replace_later.push((i, v));
                     }
 
@@ -214,121 +261,37 @@ impl<T> Node<T>
                             set_later.push((vec!((offset, start + offset), (offset + end, offset + length)), old_infos));
                         }
                     }
-                } else if child.is_leaf() {
-                    let mut new_leaf = Node::new_leaf(child.text.take().unwrap());
-                    new_leaf.give_listener(&self.listener);
-
-                    for old_info in child.infos() {
-                        new_leaf.add_info(Rc::clone(old_info));
-                    }
-
-                    replace_later.push((i, vec!(new_leaf)));
-                } else {
-                    replace_later.push((i, child.children.take().unwrap()));
                 }
 
                 offset += length;
             }
 
-            // Find and process single-item replace later nodes which consist of one
-            // unformatted leaf. If they are adjacent, they can be merged.
-            // Handle the others as usual by replacing the old child with its children.
-            let mut replace_later_single_unformatted_leafs = Vec::new();
-            let mut to_insert = Vec::new();
+            // Replace the children by their replacements (shifting indices by the nodes added and removed so far)
+            let mut added = 0;
             let mut removed = 0;
-            let mut additional_children = 0;
-            for (idx, nodes) in replace_later.into_iter() {
-                self.children.as_mut().unwrap().remove(idx - removed);
-                self.emit_event(change::Event::NodeRemoved { parent: &self, removed_idx: idx - removed });
+            for (idx, nodes) in replace_later {
+                let idx = idx + added - removed;
+                self.children_mut().remove(idx);
+                ctx.emit(change::Event::NodeRemoved { parent: &self, removed_idx: idx });
                 removed += 1;
 
-                // Replace the old node by its children.
-                let add_children = nodes.len() - 1;
-
-                let mut i = 0;
-                for node in nodes {
-                    if node.is_leaf() && node.infos.len() == 0 {
-                        replace_later_single_unformatted_leafs.push((idx + i + additional_children, node));
-                    } else {
-                        to_insert.push((idx + i + additional_children, node));
-                    }
-                    i += 1;
+                for (i, node) in nodes.into_iter().enumerate() {
+                    self.children_mut().insert(idx + i, Shared::new(node));
+                    ctx.emit(change::Event::NodeAdded { parent: &self, added_idx: idx + i });
+                    added += 1;
                 }
-
-                additional_children += add_children;
             }
 
-            if !replace_later_single_unformatted_leafs.is_empty() {
-                // Collect and merge adjacent unformatted leafs.
-                let (mut start_idx, first_node) = replace_later_single_unformatted_leafs.remove(0);
-                let mut last_idx = start_idx;
-                let mut collector = vec!((last_idx, first_node));
-
-                let mut reduced_count = 0;
-
-                let mut to_merge = Vec::new();
-                for (idx, node) in replace_later_single_unformatted_leafs {
-                    if idx == last_idx + 1 {
-                        collector.push((idx, node));
-                    } else {
-                        if collector.len() > 1 {
-                            reduced_count += collector.len() - 1;
-                            to_merge.push((start_idx, collector));
-                        } else {
-                            let (idx, nodes) = collector.remove(0);
-                            to_insert.push((idx - reduced_count, nodes));
-                        }
-                        start_idx = idx;
-                        collector = vec!((idx, node));
-                    }
-
-                    last_idx = idx;
-                }
-                if collector.len() >= 2 {
-                    to_merge.push((start_idx, collector));
-                } else {
-                    let (idx, nodes) = collector.remove(0);
-                    to_insert.push((idx - reduced_count, nodes));
-                }
-
-                // Merge adjacent unformatted leafs.
-                for (idx, nodes) in to_merge {
-                    let reduces_by = nodes.len() - 1;
-                    let mut string = String::new();
-                    for (_, n) in nodes {
-                        string.push_str(n.text.as_ref().unwrap());
-                    }
-
-                    let mut new_leaf = Node::new_leaf(string);
-                    new_leaf.give_listener(&self.listener);
-
-                    to_insert.push((idx - reduced_count, new_leaf));
-                    reduced_count += reduces_by;
-                }
-            }
-
-            // Insert remaining
-            to_insert.sort_by_key(|(idx, _)| *idx);
-            let mut child_count = self.child_count();
-            for (idx, node) in to_insert {
-                if idx >= child_count {
-                    &mut self.children.as_mut().unwrap().push(node);
-                    self.emit_event(change::Event::NodeAdded { parent: &self, added_idx: child_count });
-                } else {
-                    &mut self.children.as_mut().unwrap().insert(idx, node);
-                    self.emit_event(change::Event::NodeAdded { parent: &self, added_idx: idx });
-                }
-
-                child_count += 1;
-            }
+            // Adjacent unformatted leafs can be merged
+            self.merge_adjacent_leaves(ctx);
 
             // Check if we have only one leaf child without info left
-            if self.child_count() == 1 && self.children.as_ref().unwrap().first().unwrap().is_leaf() {
+            if self.child_count() == 1 && self.children().first().unwrap().is_leaf() {
                 // Turn this node into a leaf
-                let mut n = self.children.as_mut().unwrap().remove(0);
+                let mut n = self.remove_child(0);
                 self.children = None;
                 self.text = Some(n.text.take().unwrap());
-                self.emit_event(change::Event::NodeRemoved { parent: &self, removed_idx: 0 });
+                ctx.emit(change::Event::NodeRemoved { parent: &self, removed_idx: 0 });
 
                 let mut infos_added = false;
                 for info in n.infos.into_iter() {
@@ -337,21 +300,21 @@ impl<T> Node<T>
                 }
 
                 if infos_added {
-                    self.emit_event(change::Event::InfosChanged { node: &self });
+                    ctx.emit(change::Event::InfosChanged { node: &self });
                 }
             }
 
             if self.child_count() > 1 {
-                self.regroup_neighbors();
+                self.regroup_neighbors(ctx);
             }
         }
 
         for (ranges, old_infos) in set_later {
             for (a, b) in ranges {
                 for old_info in &old_infos {
-                    if let Some(v) = self.set(a, b, Rc::clone(old_info)) {
+                    if let Some(v) = self.set_in(a, b, Shared::clone(old_info), ctx) {
                         for n in v {
-                            self.add_child(n);
+                            self.add_child_in(n, ctx);
                         }
                     }
                 }
@@ -360,13 +323,10 @@ impl<T> Node<T>
 
         if self.infos.len() == 0 && !self.root {
             if self.is_leaf() {
-                let mut new_leaf = Node::new_leaf(self.text.take().unwrap());
-                new_leaf.give_listener(&self.listener);
-
-                Some(vec!(new_leaf))
+                Some(vec!(Node::new_leaf_in(self.text.take().unwrap(), ctx)))
             } else {
                 // This node has no use -> replace with it's children
-                Some(self.children.take().unwrap())
+                Some(self.take_children())
             }
         } else {
             None
@@ -378,10 +338,322 @@ impl<T> Node<T>
         self.infos.contains(info)
     }
 
+    /// Get all syntax/format infos effective for the character at the passed index.
+    /// Infos of outer nodes come first.
+    pub fn infos_at(&self, idx: usize) -> Vec<Shared<T>> {
+        let mut result: Vec<Shared<T>> = self.infos.iter().map(Shared::clone).collect();
+
+        if !self.is_leaf() {
+            let mut offset = 0;
+            for child in self.children() {
+                let length = child.length();
+
+                if idx < offset + length {
+                    for info in child.infos_at(idx - offset) {
+                        if !result.contains(&info) {
+                            result.push(info);
+                        }
+                    }
+                    break;
+                }
+
+                offset += length;
+            }
+        }
+
+        result
+    }
+
+    /// Check whether all text in the passed range (start index inclusive, end index exclusive) carries the passed info.
+    pub(crate) fn has_info_throughout(&self, start_idx: usize, end_idx: usize, info: &Shared<T>) -> bool {
+        if self.infos.contains(info) {
+            return true;
+        }
+
+        if self.is_leaf() {
+            return false;
+        }
+
+        let mut offset = 0;
+        for child in self.children() {
+            let length = child.length();
+            let start = start_idx.saturating_sub(offset);
+            let end = end_idx.saturating_sub(offset).min(length);
+
+            if start < end && !child.has_info_throughout(start, end, info) {
+                return false;
+            }
+
+            offset += length;
+        }
+
+        true
+    }
+
+    /// Check whether any text in the passed range (start index inclusive, end index exclusive) carries the passed info.
+    pub(crate) fn has_info_within(&self, start_idx: usize, end_idx: usize, info: &Shared<T>) -> bool {
+        if self.infos.contains(info) {
+            return true;
+        }
+
+        if self.is_leaf() {
+            return false;
+        }
+
+        let mut offset = 0;
+        for child in self.children() {
+            let length = child.length();
+            let start = start_idx.saturating_sub(offset);
+            let end = end_idx.saturating_sub(offset).min(length);
+
+            if start < end && child.has_info_within(start, end, info) {
+                return true;
+            }
+
+            offset += length;
+        }
+
+        false
+    }
+
+    /// Collect the spans of text with the same effective syntax/format infos
+    /// intersecting the passed range (start index inclusive, end index exclusive).
+    /// The collected spans are clipped to the range.
+    pub(crate) fn collect_spans(&self, start_idx: usize, end_idx: usize, offset: usize, inherited: &[Shared<T>], result: &mut Vec<Span<T>>) {
+        let mut infos = inherited.to_vec();
+        for info in &self.infos {
+            if !infos.contains(info) {
+                infos.push(Shared::clone(info));
+            }
+        }
+
+        if self.is_leaf() {
+            let text = self.text.as_ref().unwrap();
+            let start = if start_idx > offset { start_idx - offset } else { 0 };
+            let end = if end_idx - offset < text.len() { end_idx - offset } else { text.len() };
+
+            if start < end {
+                result.push(Span {
+                    start: offset + start,
+                    end: offset + end,
+                    text: String::from(&text[start..end]),
+                    infos,
+                });
+            }
+        } else {
+            let mut offset = offset;
+            for child in self.children() {
+                let length = child.length();
+
+                if offset < end_idx && start_idx < offset + length {
+                    child.collect_spans(start_idx, end_idx, offset, &infos, result);
+                }
+
+                offset += length;
+            }
+        }
+    }
+
+    /// Check the structural invariants of the node (as root of a tree) and its children.
+    /// Returns all violations found (empty if the node is consistent).
+    pub fn validate(&self) -> Vec<validation::Violation<T>> {
+        let mut violations = Vec::new();
+        self.collect_violations(true, &[], &mut violations);
+        violations
+    }
+
+    /// Check the structural invariants of the node and its children
+    /// collecting all violations found in the passed list.
+    pub(crate) fn collect_violations(&self, is_root: bool, inherited: &[Shared<T>], violations: &mut Vec<validation::Violation<T>>) {
+        if self.root != is_root {
+            violations.push(if is_root {
+                validation::Violation::MissingRootFlag { node_id: self.id }
+            } else {
+                validation::Violation::MisplacedRootFlag { node_id: self.id }
+            });
+        }
+
+        for info in &self.infos {
+            if inherited.contains(info) {
+                violations.push(validation::Violation::RedundantInfo { node_id: self.id, info: Shared::clone(info) });
+            }
+        }
+
+        if let Some(children) = &self.children {
+            if children.is_empty() {
+                violations.push(validation::Violation::EmptyChildren { node_id: self.id });
+            }
+        }
+
+        let actual = if self.is_leaf() {
+            self.text.as_ref().map_or(0, |text| text.len())
+        } else {
+            self.children().iter().map(|child| child.length()).sum()
+        };
+        if self.length != actual {
+            violations.push(validation::Violation::WrongLength { node_id: self.id, cached: self.length, actual });
+        }
+
+        if self.is_leaf() {
+            match &self.text {
+                Some(text) => if text.is_empty() && !is_root {
+                    violations.push(validation::Violation::EmptyLeaf { node_id: self.id });
+                },
+                None => violations.push(validation::Violation::MissingText { node_id: self.id }),
+            }
+        } else {
+            if self.text.is_some() {
+                violations.push(validation::Violation::TextOnInnerNode { node_id: self.id });
+            }
+
+            if self.infos.is_empty() && !is_root {
+                violations.push(validation::Violation::InnerNodeWithoutInfos { node_id: self.id });
+            }
+
+            if self.child_count() == 1 {
+                violations.push(validation::Violation::SingleChild { node_id: self.id });
+            }
+
+            let children = self.children();
+            for idx in 1..children.len() {
+                let a = &children[idx - 1];
+                let b = &children[idx];
+
+                let mergeable = a.infos.iter().any(|info| b.infos.contains(info))
+                    || (a.is_leaf() && b.is_leaf() && a.infos == b.infos);
+                if mergeable {
+                    violations.push(validation::Violation::UnmergedSiblings { parent_id: self.id, idx: idx - 1 });
+                }
+            }
+
+            let mut inherited = inherited.to_vec();
+            inherited.extend(self.infos.iter().cloned());
+            for child in children {
+                child.collect_violations(false, &inherited, violations);
+            }
+        }
+    }
+
+    /// Rebuild the node into a canonical shape for its text and effective syntax/format infos.
+    pub fn normalize(&mut self)
+        where T: Ord {
+        self.normalize_in(&mut Context::detached());
+    }
+
+    /// Rebuild the node into a canonical shape emitting change events to the passed context.
+    /// See `normalize` for details.
+    pub(crate) fn normalize_in(&mut self, ctx: &mut Context<T>)
+        where T: Ord {
+        let mut spans = Vec::new();
+        self.collect_spans(0, self.length(), 0, &[], &mut spans);
+        let spans = span::merge_adjacent(spans);
+        if spans.is_empty() {
+            return;
+        }
+
+        let normalized = Node::from_spans(&spans, &[], ctx);
+        if normalized.text == self.text && normalized.infos == self.infos && normalized.children == self.children {
+            return;
+        }
+
+        for i in (0..self.child_count()).rev() {
+            self.children_mut().remove(i);
+            ctx.emit(change::Event::NodeRemoved { parent: &self, removed_idx: i });
+        }
+
+        self.children = normalized.children;
+        self.text = normalized.text;
+        if self.is_leaf() {
+            ctx.emit(change::Event::TextChanged { node: &self });
+        } else {
+            for i in 0..self.child_count() {
+                ctx.emit(change::Event::NodeAdded { parent: &self, added_idx: i });
+            }
+        }
+
+        if self.infos != normalized.infos {
+            self.infos = normalized.infos;
+            ctx.emit(change::Event::InfosChanged { node: &self });
+        }
+    }
+
+    /// Build a root node in canonical shape for the passed spans (with adjacent spans of the same infos merged).
+    pub(crate) fn new_root_from_spans(spans: &[Span<T>], ctx: &mut Context<T>) -> Node<T>
+        where T: Ord {
+        if spans.is_empty() {
+            return Node::new_root_in("", ctx);
+        }
+
+        let mut node = Node::from_spans(spans, &[], ctx);
+        node.root = true;
+        node
+    }
+
+    /// Build a node in canonical shape for the passed spans (with adjacent spans of the same infos merged).
+    /// Infos common to all spans are set on the node, the remaining spans are grouped greedily
+    /// by the info covering the longest run of text (ties broken by the order of the infos).
+    fn from_spans(spans: &[Span<T>], inherited: &[Shared<T>], ctx: &mut Context<T>) -> Node<T>
+        where T: Ord {
+        let common: Vec<Shared<T>> = spans[0].infos.iter()
+            .filter(|info| !inherited.contains(info) && spans.iter().all(|s| s.infos.contains(info)))
+            .cloned()
+            .collect();
+
+        let mut node = if spans.len() == 1 {
+            Node::new_leaf_in(spans[0].text.clone(), ctx)
+        } else {
+            let mut inherited = inherited.to_vec();
+            inherited.extend(common.iter().cloned());
+
+            let mut node = Node::new_in(ctx);
+            let mut i = 0;
+            while i < spans.len() {
+                // Find the info covering the longest run of text starting at the current span
+                let mut best: Option<(&Shared<T>, usize, usize)> = None;
+                for info in &spans[i].infos {
+                    if inherited.contains(info) {
+                        continue;
+                    }
+
+                    let mut end = i;
+                    let mut length = 0;
+                    while end < spans.len() && spans[end].infos.contains(info) {
+                        length += spans[end].text.len();
+                        end += 1;
+                    }
+
+                    let better = match best {
+                        Some((b, _, l)) => length > l || (length == l && info < b),
+                        None => true,
+                    };
+                    if better {
+                        best = Some((info, end, length));
+                    }
+                }
+
+                let end = best.map_or(i + 1, |(_, end, _)| end);
+                node.add_child(Node::from_spans(&spans[i..end], &inherited, ctx));
+                i = end;
+            }
+            node
+        };
+
+        for info in common {
+            node.add_info(info);
+        }
+        node
+    }
+
     /// Set syntax/format info for the passed range.
     /// The range is the passed start index (inclusive) to the passed end index (exclusive).
     /// Returns a list of nodes to replace the current one in case that is needed (optional).
-    pub fn set(&mut self, start_idx: usize, end_idx: usize, info: Rc<T>) -> Option<Vec<Node<T>>> {
+    pub fn set(&mut self, start_idx: usize, end_idx: usize, info: Shared<T>) -> Option<Vec<Node<T>>> {
+        self.set_in(start_idx, end_idx, info, &mut Context::detached())
+    }
+
+    /// Set syntax/format info for the passed range emitting change events to the passed context.
+    /// See `set` for details.
+    pub(crate) fn set_in(&mut self, start_idx: usize, end_idx: usize, info: Shared<T>, ctx: &mut Context<T>) -> Option<Vec<Node<T>>> {
         assert!(start_idx < end_idx);
 
         if self.has_info(&info) {
@@ -389,47 +661,52 @@ impl<T> Node<T>
         }
 
         if self.is_leaf() {
-            self.set_on_leaf(start_idx, end_idx, info)
+            self.set_on_leaf(start_idx, end_idx, info, ctx)
         } else {
-            self.set_on_node(start_idx, end_idx, info);
+            self.set_on_node(start_idx, end_idx, info, ctx);
             None
         }
     }
 
     /// Unset the passed syntax/format info for the passed range.
     /// The range is the passed start index (inclusive) to the passed end index (exclusive).
-    pub fn unset(&mut self, start_idx: usize, end_idx: usize, info: Rc<T>) {
+    pub fn unset(&mut self, start_idx: usize, end_idx: usize, info: Shared<T>) {
+        self.unset_in(start_idx, end_idx, info, &mut Context::detached());
+    }
+
+    /// Unset the passed syntax/format info for the passed range emitting change events to the passed context.
+    pub(crate) fn unset_in(&mut self, start_idx: usize, end_idx: usize, info: Shared<T>, ctx: &mut Context<T>) {
         assert!(start_idx < end_idx);
 
-        if let Some(v) = self.remove_info(start_idx, end_idx, info, true) {
-            self.children = Some(v);
+        if let Some(v) = self.remove_info_in(start_idx, end_idx, info, true, ctx) {
+            self.children = Some(v.into_iter().map(Shared::new).collect());
         }
     }
 
     /// Set for a node with children.
-    fn set_on_node(&mut self, start_idx: usize, end_idx: usize, info: Rc<T>) {
+    fn set_on_node(&mut self, start_idx: usize, end_idx: usize, info: Shared<T>, ctx: &mut Context<T>) {
         // Check if affects only this node
         let length = self.length();
         if start_idx == 0 && end_idx == length {
             // Remove info in children -> now unnecessary
-            if let Some(v) = self.remove_info(0, length, Rc::clone(&info), true) {
-                self.children = Some(v);
+            if let Some(v) = self.remove_info_in(0, length, Shared::clone(&info), true, ctx) {
+                self.children = Some(v.into_iter().map(Shared::new).collect());
             }
 
             self.add_info(info);
-            self.emit_event(change::Event::InfosChanged { node: &self });
+            ctx.emit(change::Event::InfosChanged { node: &self });
         } else {
-            self.set_on_node_children(start_idx, end_idx, Rc::clone(&info));
+            self.set_on_node_children(start_idx, end_idx, Shared::clone(&info), ctx);
         }
     }
 
     /// Set on nodes children.
-    fn set_on_node_children(&mut self, mut start_idx: usize, end_idx: usize, info: Rc<T>) {
+    fn set_on_node_children(&mut self, mut start_idx: usize, end_idx: usize, info: Shared<T>, ctx: &mut Context<T>) {
         // Find out which child-node(s) is/are affected
         let mut offset = 0;
         let mut affected_children = Vec::new();
         for i in 0..self.child_count() {
-            let child = &self.children.as_mut().unwrap()[i];
+            let child = &self.children()[i];
             let length = child.length();
 
             if start_idx >= offset && start_idx < offset + length {
@@ -456,32 +733,18 @@ impl<T> Node<T>
         // Collect all completely enclosed child nodes.
         let completely_enclosed: Vec<&AffectedNode> = affected_children.iter().filter(|a| a.completely_enclosed).collect();
         if completely_enclosed.len() >= 2 {
-            // Build new parent node for these nodes
-            let mut parent = Node::new();
-            parent.give_listener(&self.listener);
-            parent.add_info(Rc::clone(&info));
-
-            // Remove all completely enclosed children from old parent and assign to the new one
-            let mut removed_count = 0;
-            for a in &completely_enclosed {
-                let removed_child = self.children.as_mut().unwrap().remove(a.node_index - removed_count);
-                self.emit_event(change::Event::NodeRemoved { parent: &self, removed_idx: a.node_index - removed_count });
-
-                parent.add_child(removed_child);
-                removed_count += 1;
-            }
-
-            // Insert new parent as child of the old parent
-            let insert_idx = completely_enclosed.first().as_ref().unwrap().node_index;
-            self.children.as_mut().unwrap().insert(insert_idx, parent);
-            self.emit_event(change::Event::NodeAdded { parent: &self, added_idx: insert_idx });
+            // Group all completely enclosed children under a new parent with the info
+            let indices: Vec<usize> = completely_enclosed.iter().map(|a| a.node_index).collect();
+            let insert_idx = indices[0];
+            let removed_count = indices.len();
+            self.group_children(&indices, Shared::clone(&info), ctx);
 
             // Reduce to the rest of the affected children, which have not been handled yet.
             affected_children = affected_children.into_iter()
                 .filter(|a| !a.completely_enclosed)
                 .map(|mut a| {
                     if a.node_index > insert_idx {
-                        a.node_index -= removed_count;
+                        a.node_index -= removed_count - 1;
                     }
 
                     a
@@ -493,100 +756,139 @@ impl<T> Node<T>
         for i in 0..affected_children.len() {
             let affected = &affected_children[i];
 
-            let child = &mut self.children.as_mut().unwrap()[affected.node_index];
-            if let Some(replace_with) = child.set(affected.start, affected.end, Rc::clone(&info)) {
+            let child = self.child_mut(affected.node_index);
+            if let Some(replace_with) = child.set_in(affected.start, affected.end, Shared::clone(&info), ctx) {
                 replace_later.push((affected.node_index, replace_with)); // Replace the child node with the passed nodes later.
             }
         }
 
-        // Replace the child nodes which need to
-        let mut added = 0;
+        // Replace the child nodes which need to (shifting indices by the nodes added so far)
+        let mut shift = 0;
         for (idx, replace_with) in replace_later {
-            self.children.as_mut().unwrap().remove(idx);
-            self.emit_event(change::Event::NodeRemoved { parent: &self, removed_idx: idx });
-
-            for node in replace_with {
-                self.children.as_mut().unwrap().insert(idx + added, node);
-                self.emit_event(change::Event::NodeAdded { parent: &self, added_idx: idx + added });
-                added += 1;
+            let idx = idx + shift;
+            self.children_mut().remove(idx);
+            ctx.emit(change::Event::NodeRemoved { parent: &self, removed_idx: idx });
+
+            let count = replace_with.len();
+            for (i, node) in replace_with.into_iter().enumerate() {
+                self.children_mut().insert(idx + i, Shared::new(node));
+                ctx.emit(change::Event::NodeAdded { parent: &self, added_idx: idx + i });
             }
+            shift = shift + count - 1;
         }
 
-        self.regroup_neighbors();
+        self.regroup_neighbors(ctx);
     }
 
     /// Regroup neighboring nodes with similar syntax/format info.
-    fn regroup_neighbors(&mut self) {
-        if let Some((info, indices)) = self.find_max_similar_neighbors() {
-            // Create new parent node for the similar nodes
-            let mut parent = Node::new();
-            parent.give_listener(&self.listener);
+    fn regroup_neighbors(&mut self, ctx: &mut Context<T>) {
+        while let Some((info, indices)) = self.find_max_similar_neighbors() {
+            self.group_children(&indices, info, ctx);
 
-            let insert_idx = indices[0];
+            // Check if we have only one child left with the same syntax/format info as this node
+            if self.child_count() == 1 {
+                self.merge_with_only_child(ctx);
+                break;
+            }
+        }
+    }
 
-            let mut removed = 0;
-            let mut to_add = Vec::new();
-            for idx in indices {
-                let mut child = self.children.as_mut().unwrap().remove(idx - removed);
-                self.emit_event(change::Event::NodeRemoved { parent: &self, removed_idx: idx - removed });
+    /// Move the children at the passed (adjacent) indices to a new parent node carrying the passed info.
+    /// The info is removed from the moved children since the new parent carries it.
+    fn group_children(&mut self, indices: &[usize], info: Shared<T>, ctx: &mut Context<T>) {
+        // Create new parent node for the children
+        let mut parent = Node::new_in(ctx);
 
-                match child.remove_info(0, child.length(), Rc::clone(&info), true) {
-                    Some(v) => {
-                        for n in v {
-                            to_add.push(n);
-                        }
+        let insert_idx = indices[0];
+
+        let mut removed = 0;
+        let mut to_add = Vec::new();
+        for idx in indices {
+            let mut child = self.remove_child(idx - removed);
+            ctx.emit(change::Event::NodeRemoved { parent: &self, removed_idx: idx - removed });
+
+            match child.remove_info_in(0, child.length(), Shared::clone(&info), true, ctx) {
+                Some(v) => {
+                    for n in v {
+                        to_add.push(n);
                     }
-                    None => to_add.push(child),
                 }
-                removed += 1;
+                None => to_add.push(child),
             }
+            removed += 1;
+        }
 
-            if to_add.iter().all(|n| n.infos.len() == 0) {
-                // Merge all children
-                let mut string = String::new();
-                for mut n in to_add {
-                    string.push_str(&n.text.take().unwrap());
-                }
-                parent.text = Some(string);
-            } else {
-                for n in to_add {
-                    parent.add_child(n);
-                }
-                parent.regroup_neighbors();
+        if to_add.iter().all(|n| n.is_leaf() && n.infos.len() == 0) {
+            // Merge all children
+            let mut string = String::new();
+            for mut n in to_add {
+                string.push_str(&n.text.take().unwrap());
+            }
+            parent.length = string.len();
+            parent.text = Some(string);
+        } else {
+            for n in to_add {
+                parent.add_child_in(n, ctx);
             }
+            parent.merge_adjacent_leaves(ctx);
+            parent.regroup_neighbors(ctx);
+        }
 
-            parent.add_info(info);
+        parent.add_info(info);
 
-            self.children.as_mut().unwrap().insert(insert_idx, parent);
-            self.emit_event(change::Event::NodeAdded { parent: &self, added_idx: insert_idx });
+        self.children_mut().insert(insert_idx, Shared::new(parent));
+        ctx.emit(change::Event::NodeAdded { parent: &self, added_idx: insert_idx });
+    }
 
-            // Check if we have only one child left with the same syntax/format info as this node
-            if self.child_count() == 1 {
-                // Merge node with child
-                let mut child = self.children.as_mut().unwrap().remove(0);
-                self.emit_event(change::Event::NodeRemoved { parent: &self, removed_idx: 0 });
+    /// Merge the node with its only child taking over the childs text or children and infos.
+    fn merge_with_only_child(&mut self, ctx: &mut Context<T>) {
+        let mut child = self.remove_child(0);
+        ctx.emit(change::Event::NodeRemoved { parent: &self, removed_idx: 0 });
 
-                self.children = Some(child.children.take().unwrap());
-                for i in 0..self.child_count() {
-                    self.emit_event(change::Event::NodeAdded { parent: &self, added_idx: i });
-                }
+        if child.is_leaf() {
+            self.children = None;
+            self.text = child.text.take();
+            ctx.emit(change::Event::TextChanged { node: &self });
+        } else {
+            self.children = child.children.take();
+            for i in 0..self.child_count() {
+                ctx.emit(change::Event::NodeAdded { parent: &self, added_idx: i });
+            }
+        }
 
-                if child.infos.len() > 0 {
-                    for i in child.infos {
-                        self.add_info(i);
-                    }
-                    self.emit_event(change::Event::InfosChanged { node: &self });
-                }
+        if child.infos.len() > 0 {
+            for i in child.infos {
+                self.add_info(i);
+            }
+            ctx.emit(change::Event::InfosChanged { node: &self });
+        }
+    }
+
+    /// Merge adjacent leaf children carrying the same syntax/format infos.
+    fn merge_adjacent_leaves(&mut self, ctx: &mut Context<T>) {
+        let mut i = 1;
+        while i < self.child_count() {
+            let children = self.children();
+            if children[i - 1].is_leaf() && children[i].is_leaf() && children[i - 1].infos == children[i].infos {
+                let right = self.children_mut().remove(i);
+                ctx.emit(change::Event::NodeRemoved { parent: &self, removed_idx: i });
+
+                let left = self.child_mut(i - 1);
+                left.text.as_mut().unwrap().push_str(right.text.as_ref().unwrap());
+                left.length += right.length;
+                ctx.emit(change::Event::TextChanged { node: &self.children()[i - 1] });
+            } else {
+                i += 1;
             }
         }
     }
 
     /// Find the maximum similar neighbors in the nodes children.
-    fn find_max_similar_neighbors<'a>(&self) -> Option<(Rc<T>, Vec<usize>)> {
-        let children = self.children.as_ref().unwrap();
+    fn find_max_similar_neighbors<'a>(&self) -> Option<(Shared<T>, Vec<usize>)> {
+        let children = self.children();
         let length = children.len();
 
-        let mut max_result: Option<(Rc<T>, Vec<usize>)> = None;
+        let mut max_result: Option<(Shared<T>, Vec<usize>)> = None;
         for i in 0..length {
             let child = &children[i];
 
@@ -602,7 +904,7 @@ impl<T> Node<T>
                 }
 
                 if similar.len() > 1 && (max_result.is_none() || max_result.as_ref().unwrap().1.len() < similar.len()) {
-                    max_result = Some((Rc::clone(info), similar));
+                    max_result = Some((Shared::clone(info), similar));
                 }
             }
         }
@@ -613,7 +915,7 @@ impl<T> Node<T>
     /// Set for a leaf node.
     /// Returns a list of nodes to replace this leaf in the parent children list when
     /// there is something to replace.
-    fn set_on_leaf(&mut self, start_idx: usize, end_idx: usize, info: Rc<T>) -> Option<Vec<Node<T>>> {
+    fn set_on_leaf(&mut self, start_idx: usize, end_idx: usize, info: Shared<T>, ctx: &mut Context<T>) -> Option<Vec<Node<T>>> {
         let text = self.text.take().unwrap();
         let length = text.len();
         let has_infos = self.infos.len() > 0;
@@ -625,56 +927,49 @@ impl<T> Node<T>
             // Affects exactly this one leaf node
             self.add_info(info);
             self.text = Some(text);
-            self.emit_event(change::Event::InfosChanged { node: &self });
+            ctx.emit(change::Event::InfosChanged { node: &self });
             None
         } else if start_idx == 0 {
             // Split this leaf in two leafs
-            let mut left_node = Node::new_leaf(String::from(&text[0..end_idx]));
-            left_node.give_listener(&self.listener);
+            let mut left_node = Node::new_leaf_in(String::from(&text[0..end_idx]), ctx);
             left_node.add_info(info);
 
-            let mut right_node = Node::new_leaf(String::from(&text[end_idx..length]));
-            right_node.give_listener(&self.listener);
+            let right_node = Node::new_leaf_in(String::from(&text[end_idx..length]), ctx);
 
             if has_infos || self.root {
-                self.add_child(left_node);
-                self.add_child(right_node);
+                self.add_child_in(left_node, ctx);
+                self.add_child_in(right_node, ctx);
                 None
             } else {
                 Some(vec!(left_node, right_node))
             }
         } else if end_idx == length {
             // Split this leaf in two leafs
-            let mut left_node = Node::new_leaf(String::from(&text[0..start_idx]));
-            left_node.give_listener(&self.listener);
+            let left_node = Node::new_leaf_in(String::from(&text[0..start_idx]), ctx);
 
-            let mut right_node = Node::new_leaf(String::from(&text[start_idx..length]));
-            right_node.give_listener(&self.listener);
+            let mut right_node = Node::new_leaf_in(String::from(&text[start_idx..length]), ctx);
             right_node.add_info(info);
 
             if has_infos || self.root {
-                self.add_child(left_node);
-                self.add_child(right_node);
+                self.add_child_in(left_node, ctx);
+                self.add_child_in(right_node, ctx);
                 None
             } else {
                 Some(vec!(left_node, right_node))
             }
         } else {
             // Turn this leaf in three leafs
-            let mut left_node = Node::new_leaf(String::from(&text[0..start_idx]));
-            left_node.give_listener(&self.listener);
+            let left_node = Node::new_leaf_in(String::from(&text[0..start_idx]), ctx);
 
-            let mut middle_node = Node::new_leaf(String::from(&text[start_idx..end_idx]));
-            middle_node.give_listener(&self.listener);
+            let mut middle_node = Node::new_leaf_in(String::from(&text[start_idx..end_idx]), ctx);
             middle_node.add_info(info);
 
-            let mut right_node = Node::new_leaf(String::from(&text[end_idx..length]));
-            right_node.give_listener(&self.listener);
+            let right_node = Node::new_leaf_in(String::from(&text[end_idx..length]), ctx);
 
             if has_infos || self.root {
-                self.add_child(left_node);
-                self.add_child(middle_node);
-                self.add_child(right_node);
+                self.add_child_in(left_node, ctx);
+                self.add_child_in(middle_node, ctx);
+                self.add_child_in(right_node, ctx);
                 None
             } else {
                 Some(vec!(left_node, middle_node, right_node))
@@ -687,29 +982,35 @@ impl<T> Node<T>
         if self.is_leaf() {
             let length = self.length();
 
-            if idx >= length {
+            if idx > length {
                 panic!("Cannot insert at position {} when underlying text has length {}", idx, length);
             }
 
             self.text.as_mut().unwrap().insert(idx, ch);
-            self.emit_event(change::Event::TextChanged { node: &self });
+            self.length += ch.len_utf8();
         } else {
             let mut offset = 0;
-            for child in self.children.as_mut().unwrap() {
-                let length = child.length();
+            for i in 0..self.child_count() {
+                let length = self.children()[i].length();
 
                 if idx <= offset + length {
-                    child.insert(idx - offset, ch);
+                    self.child_mut(i).insert(idx - offset, ch);
+                    self.length += ch.len_utf8();
                     break;
                 }
 
-                offset += child.length();
+                offset += length;
             }
         }
     }
 
     /// Insert a string in the underlying text.
     pub fn insert_str(&mut self, idx: usize, string: &str) {
+        self.insert_str_in(idx, string, &mut Context::detached());
+    }
+
+    /// Insert a string in the underlying text emitting change events to the passed context.
+    pub(crate) fn insert_str_in(&mut self, idx: usize, string: &str, ctx: &mut Context<T>) {
         if self.is_leaf() {
             let length = self.length();
 
@@ -718,18 +1019,20 @@ impl<T> Node<T>
             }
 
             self.text.as_mut().unwrap().insert_str(idx, string);
-            self.emit_event(change::Event::TextChanged { node: &self });
+            self.length += string.len();
+            ctx.emit(change::Event::TextChanged { node: &self });
         } else {
             let mut offset = 0;
-            for child in self.children.as_mut().unwrap() {
-                let length = child.length();
+            for i in 0..self.child_count() {
+                let length = self.children()[i].length();
 
                 if idx <= offset + length {
-                    child.insert_str(idx - offset, string);
+                    self.child_mut(i).insert_str_in(idx - offset, string, ctx);
+                    self.length += string.len();
                     break;
                 }
 
-                offset += child.length();
+                offset += length;
             }
         }
     }
@@ -738,20 +1041,20 @@ impl<T> Node<T>
     pub fn push(&mut self, ch: char) {
         if self.is_leaf() {
             self.text.as_mut().unwrap().push(ch);
-            self.emit_event(change::Event::TextChanged { node: &self });
         } else {
-            self.children.as_mut().unwrap().last_mut().unwrap().push(ch);
+            Shared::make_mut(self.children_mut().last_mut().unwrap()).push(ch);
         }
+        self.length += ch.len_utf8();
     }
 
     /// Push a string to the underlying text.
     pub fn push_str(&mut self, string: &str) {
         if self.is_leaf() {
             self.text.as_mut().unwrap().push_str(string);
-            self.emit_event(change::Event::TextChanged { node: &self });
         } else {
-            self.children.as_mut().unwrap().last_mut().unwrap().push_str(string);
+            Shared::make_mut(self.children_mut().last_mut().unwrap()).push_str(string);
         }
+        self.length += string.len();
     }
 
     /// Get the count of children under this node.
@@ -767,24 +1070,36 @@ impl<T> Node<T>
     /// Method will return boolean tuple with two elements (bool, bool).
     /// The first boolean will determine whether the node is unnecessary now,
     /// the second boolean determined whether parent may need to regroup its children.
-    pub fn remove(&mut self, mut idx: usize, mut count: usize) -> (bool, bool) {
+    pub fn remove(&mut self, idx: usize, count: usize) -> (bool, bool) {
+        let was_leaf = self.is_leaf();
+        let unnecessary = self.remove_in(idx, count, &mut Context::detached());
+
+        // Having been merged with its only child the node carries new infos its siblings might share
+        (unnecessary, !was_leaf && self.is_leaf() && !unnecessary)
+    }
+
+    /// Remove a count of characters from the underlying text emitting change events to the passed context.
+    /// Returns whether the node is unnecessary now (having no text left).
+    pub(crate) fn remove_in(&mut self, mut idx: usize, mut count: usize, ctx: &mut Context<T>) -> bool {
         let length = self.length();
 
         if self.is_leaf() {
             assert!(idx + count <= length);
 
+            self.length -= count;
             self.text.as_mut().unwrap().replace_range(idx..idx + count, "");
             if self.length() > 0 {
-                self.emit_event(change::Event::TextChanged { node: &self });
+                ctx.emit(change::Event::TextChanged { node: &self });
             }
         } else {
+            // Text beyond the end of the node is left alone
+            self.length -= count.min(length.saturating_sub(idx));
+
             // Remove from affected children
             let mut offset = 0;
             let mut remove_later = Vec::new();
-            let mut may_need_regroup = false;
             for i in 0..self.child_count() {
-                let child = &mut self.children.as_mut().unwrap()[i];
-                let length = child.length();
+                let length = self.children()[i].length();
 
                 if idx >= offset && idx < offset + length {
                     // Affects child
@@ -792,11 +1107,10 @@ impl<T> Node<T>
                     let end = if idx + count < max_end { idx + count } else { max_end };
 
                     let remove_count = end - idx;
-                    let (unnecessary, needs_regroup) = child.remove(idx - offset, remove_count);
+                    let unnecessary = self.child_mut(i).remove_in(idx - offset, remove_count, ctx);
                     if unnecessary {
                         remove_later.push(i);
                     }
-                    may_need_regroup = may_need_regroup || needs_regroup;
 
                     if idx + count <= max_end {
                         break; // Next child is not affected
@@ -812,50 +1126,58 @@ impl<T> Node<T>
             // Remove now unnecessary children
             let mut removed = 0;
             for i in remove_later {
-                self.children.as_mut().unwrap().remove(i - removed);
-                self.emit_event(change::Event::NodeRemoved { parent: &self, removed_idx: i - removed });
+                self.children_mut().remove(i - removed);
+                ctx.emit(change::Event::NodeRemoved { parent: &self, removed_idx: i - removed });
                 removed += 1;
             }
 
+            // Children formerly separated by the removed text might be adjacent now
+            self.merge_adjacent_leaves(ctx);
+
             // Check if having only one child left
             if self.child_count() == 1 {
-                let mut child = self.children.as_mut().unwrap().remove(0);
-                self.children = None;
-                self.text = Some(child.text.take().unwrap());
-                self.emit_event(change::Event::NodeRemoved { parent: &self, removed_idx: 0 });
-
-                for info in child.infos {
-                    self.add_info(info);
-                }
-                self.emit_event(change::Event::InfosChanged { node: &self });
-
-                return (self.length() == 0, true);
-            } else if self.children.as_ref().unwrap().is_empty() {
+                self.merge_with_only_child(ctx);
+            } else if self.children().is_empty() {
                 self.children = None;
                 self.text = Some(String::from(""));
-            } else if may_need_regroup {
-                self.regroup_neighbors();
+            } else {
+                self.regroup_neighbors(ctx);
             }
         }
 
-        (self.length() == 0, false)
+        self.length() == 0
     }
 
     /// Get a slice of all children under this node.
-    pub fn children(&self) -> &[Node<T>] {
+    pub fn children(&self) -> &[Shared<Node<T>>] {
         &self.children.as_ref().unwrap()[..]
     }
 
-    /// Give node change listener.
-    pub fn give_listener(&mut self, l: &Option<Rc<change::Listener<T>>>) {
-        if let Some(v) = l {
-            self.listener = Some(Rc::clone(v));
-        }
+    /// Get the list of children under this node for mutation.
+    /// The children themselves stay shared with snapshots, see `child_mut`.
+    fn children_mut(&mut self) -> &mut Vec<Shared<Node<T>>> {
+        self.children.as_mut().unwrap()
+    }
+
+    /// Get the child at the passed index for mutation.
+    /// A child shared with a snapshot is copied first (sharing its own children).
+    fn child_mut(&mut self, idx: usize) -> &mut Node<T> {
+        Shared::make_mut(&mut self.children_mut()[idx])
+    }
+
+    /// Remove the child at the passed index.
+    /// A child shared with a snapshot is copied (sharing its own children).
+    fn remove_child(&mut self, idx: usize) -> Node<T> {
+        let child = self.children_mut().remove(idx);
+        Shared::try_unwrap(child).unwrap_or_else(|c| Node::clone(&c))
     }
 
-    /// Take the change listener from this node (if any).
-    pub fn take_listener(&mut self) -> Option<Rc<change::Listener<T>>> {
-        self.listener.take()
+    /// Take the children from this node.
+    /// Children shared with a snapshot are copied (sharing their own children).
+    fn take_children(&mut self) -> Vec<Node<T>> {
+        self.children.take().unwrap().into_iter()
+            .map(|c| Shared::try_unwrap(c).unwrap_or_else(|c| Node::clone(&c)))
+            .collect()
     }
 
     /// Get a depth first pre order iterator.
@@ -863,24 +1185,107 @@ impl<T> Node<T>
         iterator::PreOrder::new(self)
     }
 
+    /// Get a depth first post order iterator.
+    pub fn post_order_iter(&self) -> iterator::PostOrder<T> {
+        iterator::PostOrder::new(self)
+    }
+
+    /// Get a depth first reverse pre order iterator.
+    pub fn reverse_pre_order_iter(&self) -> iterator::ReversePreOrder<T> {
+        iterator::ReversePreOrder::new(self)
+    }
+
+    /// Get a breadth first iterator.
+    pub fn breadth_first_iter(&self) -> iterator::BreadthFirst<T> {
+        iterator::BreadthFirst::new(self)
+    }
+
+    /// Get a depth first pre order iterator over the nodes intersecting the passed range.
+    /// The range is the passed start index (inclusive) to the passed end index (exclusive).
+    pub fn iter_range(&self, start_idx: usize, end_idx: usize) -> iterator::Range<T> {
+        iterator::Range::new(self, start_idx, end_idx)
+    }
+
+    /// Find the item of the node with the passed ID in the tree under this node.
+    pub fn find(&self, id: NodeId) -> Option<iterator::Item<T>> {
+        self.pre_order_iter().find(|item| item.node.id() == id)
+    }
+
+    /// Get an iterator over the ancestors of the node with the passed ID
+    /// from its parent up to this node (if the node is under this node).
+    pub fn ancestors(&self, id: NodeId) -> Option<iterator::Ancestors<T>> {
+        self.find(id).map(|item| iterator::Ancestors::new(self, &item.path.to_vec()))
+    }
+
+    /// Assign new IDs taken from the passed context to this node and its children.
+    pub(crate) fn reassign_ids(&mut self, ctx: &mut Context<T>) {
+        self.id = ctx.next_id();
+
+        if !self.is_leaf() {
+            for child in self.children_mut() {
+                Shared::make_mut(child).reassign_ids(ctx);
+            }
+        }
+    }
+
+    /// Visit this node and its children in pre order with the passed visitor.
+    /// Children shared with a snapshot are copied first.
+    pub(crate) fn walk_mut<F>(&mut self, level: usize, path: &mut Vec<usize>, visitor: &mut F)
+        where F: FnMut(&mut visit::NodeMut<T>) {
+        visitor(&mut visit::NodeMut::new(self, level, path));
+
+        if !self.is_leaf() {
+            for (idx, child) in self.children_mut().iter_mut().enumerate() {
+                path.push(idx);
+                Shared::make_mut(child).walk_mut(level + 1, path, visitor);
+                path.pop();
+            }
+
+            // Texts of leaves under this node might have been changed by the visitor
+            self.length = self.children().iter().map(|child| child.length()).sum();
+        }
+    }
+
     /// Get a leaf iterator.
     pub fn leaf_iter(&self) -> impl Iterator<Item=iterator::Item<T>> {
         self.pre_order_iter().filter(|item| item.node.is_leaf())
     }
+}
 
-    /// Emit a change event.
-    fn emit_event(&self, event: change::Event<T>) {
-        if let Some(l) = &self.listener {
-            l(event);
+impl<T> Clone for Node<T> {
+    /// Copy the node sharing its children and infos with the original (only its own text is copied).
+    /// The copy keeps the ID of the original.
+    fn clone(&self) -> Self {
+        Node {
+            id: self.id,
+            children: self.children.clone(),
+            infos: self.infos.clone(),
+            text: self.text.clone(),
+            length: self.length,
+            root: self.root,
         }
     }
 }
 
+impl<T> PartialEq for Node<T>
+    where T: Eq + Hash {
+    /// Compare text, infos and children of the nodes (ignoring node IDs).
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root
+            && self.text == other.text
+            && self.infos == other.infos
+            && self.children == other.children
+    }
+}
+
+impl<T> Eq for Node<T>
+    where T: Eq + Hash {}
+
 impl<T> fmt::Debug for Node<T>
     where T: Ord + Hash + Debug {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for iterator::Item { node, level } in self.pre_order_iter() {
-            let mut sorted_infos: Vec<&Rc<T>> = node.infos().collect();
+        for iterator::Item { node, level, .. } in self.pre_order_iter() {
+            let mut sorted_infos: Vec<&Shared<T>> = node.infos().collect();
             sorted_infos.sort();
 
             writeln!(
@@ -0,0 +1,103 @@
+use std::fmt;
+use crate::{Node, iterator, id};
+use crate::info::Shared;
+use std::hash::Hash;
+use std::fmt::Debug;
+
+/// Immutable snapshot of a syntax tree.
+/// Nodes are shared with the tree (and other snapshots) until the tree changes them.
+pub struct Snapshot<T> {
+    /// The snapshots root node.
+    root: Shared<Node<T>>,
+}
+
+impl<T> Snapshot<T>
+    where T: Eq + Hash {
+    /// Create new snapshot of the passed root node.
+    pub(crate) fn new(root: Shared<Node<T>>) -> Snapshot<T> {
+        Snapshot {
+            root,
+        }
+    }
+
+    /// Get the underlying text.
+    pub fn text(&self) -> String {
+        self.root.text()
+    }
+
+    /// Get the length of the underlying text.
+    pub fn length(&self) -> usize {
+        self.root.length()
+    }
+
+    /// Get the root node.
+    pub fn get_root(&self) -> &Node<T> {
+        &self.root
+    }
+
+    /// Find the node with the passed ID.
+    pub fn find_node(&self, id: id::NodeId) -> Option<&Node<T>> {
+        self.root.find(id).map(|item| item.node)
+    }
+
+    /// Get the indices of the children to descend to from the root to reach the node with the passed ID.
+    pub fn path_of(&self, id: id::NodeId) -> Option<Vec<usize>> {
+        self.root.find(id).map(|item| item.path.to_vec())
+    }
+
+    /// Get the absolute start offset of the text of the node with the passed ID.
+    pub fn offset_of(&self, id: id::NodeId) -> Option<usize> {
+        self.root.find(id).map(|item| item.start)
+    }
+
+    /// Get an iterator over the ancestors of the node with the passed ID from its parent up to the root.
+    pub fn ancestors(&self, id: id::NodeId) -> Option<iterator::Ancestors<T>> {
+        self.root.ancestors(id)
+    }
+
+    /// Get a depth first pre order iterator.
+    pub fn pre_order_iter(&self) -> iterator::PreOrder<T> {
+        self.root.pre_order_iter()
+    }
+
+    /// Get a depth first post order iterator.
+    pub fn post_order_iter(&self) -> iterator::PostOrder<T> {
+        self.root.post_order_iter()
+    }
+
+    /// Get a depth first reverse pre order iterator.
+    pub fn reverse_pre_order_iter(&self) -> iterator::ReversePreOrder<T> {
+        self.root.reverse_pre_order_iter()
+    }
+
+    /// Get a breadth first iterator.
+    pub fn breadth_first_iter(&self) -> iterator::BreadthFirst<T> {
+        self.root.breadth_first_iter()
+    }
+
+    /// Get a depth first pre order iterator over the nodes intersecting the passed range.
+    /// The range is the passed start index (inclusive) to the passed end index (exclusive).
+    pub fn iter_range(&self, start_idx: usize, end_idx: usize) -> iterator::Range<T> {
+        self.root.iter_range(start_idx, end_idx)
+    }
+
+    /// Get a leaf iterator.
+    pub fn leaf_iter(&self) -> impl Iterator<Item=iterator::Item<T>> {
+        self.root.leaf_iter()
+    }
+}
+
+impl<T> Clone for Snapshot<T> {
+    fn clone(&self) -> Self {
+        Snapshot {
+            root: Shared::clone(&self.root),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Snapshot<T>
+    where T: Ord + Hash + Debug {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.root)
+    }
+}
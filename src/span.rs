@@ -0,0 +1,96 @@
+use crate::info::Shared;
+
+/// Run of text sharing the same effective syntax/format infos.
+pub(crate) struct Span<T> {
+    /// Start index of the span (inclusive).
+    pub start: usize,
+
+    /// End index of the span (exclusive).
+    pub end: usize,
+
+    /// Text of the span.
+    pub text: String,
+
+    /// Effective syntax/format infos of the span.
+    pub infos: Vec<Shared<T>>,
+}
+
+impl<T> Span<T>
+    where T: Eq {
+    /// Check whether the passed span carries the same set of infos.
+    pub fn has_same_infos(&self, other: &Span<T>) -> bool {
+        self.infos.len() == other.infos.len() && self.infos.iter().all(|info| other.infos.contains(info))
+    }
+}
+
+/// Merge adjacent spans carrying the same set of infos.
+pub(crate) fn merge_adjacent<T>(spans: Vec<Span<T>>) -> Vec<Span<T>>
+    where T: Eq {
+    let mut result: Vec<Span<T>> = Vec::with_capacity(spans.len());
+    for span in spans {
+        match result.last_mut() {
+            Some(last) if last.end == span.start && last.has_same_infos(&span) => {
+                last.end = span.end;
+                last.text.push_str(&span.text);
+            }
+            _ => result.push(span),
+        }
+    }
+    result
+}
+
+/// Syntax/format info along with the range it applies to (start inclusive, end exclusive).
+pub(crate) type InfoRange<T> = (Shared<T>, usize, usize);
+
+/// Add the passed range for the passed info to the list of ranges.
+/// Merges the range with an adjacent range of the same info if there is one.
+pub(crate) fn add_range<T>(ranges: &mut Vec<InfoRange<T>>, info: &Shared<T>, start: usize, end: usize)
+    where T: Eq {
+    for (i, _, e) in ranges.iter_mut() {
+        if *e == start && **i == **info {
+            *e = end;
+            return;
+        }
+    }
+
+    ranges.push((Shared::clone(info), start, end));
+}
+
+/// Get the info ranges to unset and the info ranges to set in order to turn the formatting
+/// of the current spans into the formatting of the desired spans.
+/// Both lists of spans must cover the same range of text.
+pub(crate) fn info_changes<T>(current: &[Span<T>], desired: &[Span<T>]) -> (Vec<InfoRange<T>>, Vec<InfoRange<T>>)
+    where T: Eq {
+    let mut to_unset = Vec::new();
+    let mut to_set = Vec::new();
+
+    let mut i = 0;
+    let mut j = 0;
+    while i < current.len() && j < desired.len() {
+        let c = &current[i];
+        let d = &desired[j];
+
+        let start = if c.start > d.start { c.start } else { d.start };
+        let end = if c.end < d.end { c.end } else { d.end };
+        if start < end {
+            for info in &c.infos {
+                if !d.infos.contains(info) {
+                    add_range(&mut to_unset, info, start, end);
+                }
+            }
+            for info in &d.infos {
+                if !c.infos.contains(info) {
+                    add_range(&mut to_set, info, start, end);
+                }
+            }
+        }
+
+        if c.end <= d.end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    (to_unset, to_set)
+}
@@ -1,59 +1,186 @@
 use std::fmt;
-use crate::{Node, iterator, change};
-use std::rc::Rc;
-use std::hash::Hash;
+use crate::{Node, Snapshot, iterator, change, info, diff, validation, visit, id};
+use crate::info::{Expand, Shared};
+use crate::span::{self, Span};
+use std::hash::{Hash, Hasher};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
 use std::fmt::Debug;
 
 /// The syntax tree struct.
 pub struct Tree<T> {
     /// The trees root node.
-    root: Node<T>,
+    root: Shared<Node<T>>,
+
+    /// Listeners subscribed to change events of the tree.
+    subscriptions: change::Subscriptions<T>,
+
+    /// Function deciding how infos expand when inserting text at their boundaries.
+    expand_behavior: Option<info::ExpandBehavior<T>>,
+
+    /// Syntax/format infos toggled for the next text inserted at the caret index.
+    pending: Option<Pending<T>>,
+
+    /// Generator of the IDs for new nodes.
+    ids: id::BoxedGenerator,
+}
+
+/// Syntax/format infos toggled for the next text inserted at a caret index.
+/// The toggles are applied to the infos the text would carry otherwise at the time of insertion.
+struct Pending<T> {
+    /// Caret index the infos have been toggled at.
+    idx: usize,
+
+    /// Infos the inserted text will carry in addition.
+    added: Vec<Shared<T>>,
+
+    /// Infos the inserted text will not carry.
+    removed: Vec<Shared<T>>,
 }
 
 impl<T> Tree<T>
     where T: Eq + Hash {
     /// Create new tree.
+    /// The passed listener (if any) is subscribed to change events.
     pub fn new(string: &str, listener: Option<change::Listener<T>>) -> Tree<T> {
-        let mut root = Node::new_root(string);
+        let mut subscriptions = change::Subscriptions::new();
         if let Some(l) = listener {
-            root.give_listener(&Some(Rc::new(l)));
+            subscriptions.subscribe(l);
         }
 
+        let mut ids: id::BoxedGenerator = Box::new(id::Sequential::new());
+        let root = Node::new_root_in(string, &mut change::Context::new(&subscriptions, ids.as_mut()));
+
         Tree {
-            root,
+            root: Shared::new(root),
+            subscriptions,
+            expand_behavior: None,
+            pending: None,
+            ids,
         }
     }
 
+    /// Subscribe the passed listener to change events of the tree.
+    /// Returns an ID to unsubscribe the listener with later.
+    pub fn subscribe(&mut self, listener: change::Listener<T>) -> change::SubscriptionId {
+        self.subscriptions.subscribe(listener)
+    }
+
+    /// Unsubscribe the listener with the passed ID from change events of the tree.
+    /// Returns whether the listener was subscribed.
+    pub fn unsubscribe(&mut self, id: change::SubscriptionId) -> bool {
+        self.subscriptions.unsubscribe(id)
+    }
+
+    /// Set the function deciding how syntax/format infos expand when text is
+    /// inserted at the boundary of a range carrying them.
+    /// Infos expand with `Expand::InclusiveEnd` when no behavior has been set.
+    pub fn set_expand_behavior(&mut self, behavior: info::ExpandBehavior<T>) {
+        self.expand_behavior = Some(behavior);
+    }
+
+    /// Set the generator of the IDs for new nodes.
+    /// The IDs of the existing nodes are reassigned by the passed generator.
+    /// Nodes get sequential IDs starting at zero when no generator has been set.
+    pub fn set_id_generator(&mut self, generator: id::BoxedGenerator) {
+        self.ids = generator;
+        Shared::make_mut(&mut self.root).reassign_ids(&mut change::Context::new(&self.subscriptions, self.ids.as_mut()));
+    }
+
     /// Set syntax/format info for the passed range.
     /// The range is the passed start index (inclusive) to the passed end index (exclusive).
+    /// An empty range marks the info as pending for the next text inserted at the caret index.
     pub fn set(&mut self, start_idx: usize, end_idx: usize, info: T) {
-        self.root.set(start_idx, end_idx, Rc::new(info));
+        if start_idx == end_idx {
+            let pending = self.pending_at(start_idx);
+            pending.removed.retain(|i| **i != info);
+            if !pending.added.iter().any(|i| **i == info) {
+                pending.added.push(Shared::new(info));
+            }
+        } else {
+            self.set_shared(start_idx, end_idx, Shared::new(info));
+        }
     }
 
     /// Unset the passed syntax/format info for the passed range.
     /// The range is the passed start index (inclusive) to the passed end index (exclusive).
+    /// An empty range prevents the next text inserted at the caret index from carrying the info.
     pub fn unset(&mut self, start_idx: usize, end_idx: usize, info: T) {
-        self.root.unset(start_idx, end_idx, Rc::new(info));
+        if start_idx == end_idx {
+            let pending = self.pending_at(start_idx);
+            pending.added.retain(|i| **i != info);
+            if !pending.removed.iter().any(|i| **i == info) {
+                pending.removed.push(Shared::new(info));
+            }
+        } else {
+            self.unset_shared(start_idx, end_idx, Shared::new(info));
+        }
+    }
+
+    /// Get the syntax/format infos pending for the next text inserted at the passed caret index.
+    pub fn pending_infos(&self, idx: usize) -> Option<Vec<Shared<T>>> {
+        match &self.pending {
+            Some(pending) if pending.idx == idx => {
+                let mut infos = self.expanded_infos(idx);
+                infos.retain(|info| !pending.removed.contains(info));
+                for info in &pending.added {
+                    if !infos.contains(info) {
+                        infos.push(Shared::clone(info));
+                    }
+                }
+                Some(infos)
+            }
+            _ => None,
+        }
+    }
+
+    /// Notify the tree that the caret moved to the passed index.
+    /// Pending infos are cleared when the caret leaves the index they have been stored for.
+    pub fn move_caret(&mut self, idx: usize) {
+        if !self.is_pending_at(idx) {
+            self.pending = None;
+        }
     }
 
     /// Insert a char in the underlying text.
+    /// The char will carry the infos of its neighbors depending on their expand behavior.
     pub fn insert(&mut self, idx: usize, ch: char) {
-        self.root.insert(idx, ch);
+        self.insert_str(idx, ch.encode_utf8(&mut [0; 4]));
     }
 
     /// Insert a string in the underlying text.
+    /// The string will carry the pending infos at the index (if any) or otherwise
+    /// the infos of its neighbors depending on their expand behavior.
     pub fn insert_str(&mut self, idx: usize, string: &str) {
-        self.root.insert_str(idx, string);
+        let infos = match self.pending_infos(idx) {
+            Some(infos) => infos,
+            None => self.expanded_infos(idx),
+        };
+        self.pending = None;
+        self.insert_str_with(idx, string, &infos);
+    }
+
+    /// Insert a string in the underlying text carrying exactly the passed syntax/format infos.
+    pub fn insert_str_with_infos(&mut self, idx: usize, string: &str, infos: Vec<T>) {
+        self.pending = None;
+
+        let infos: Vec<Shared<T>> = infos.into_iter().map(Shared::new).collect();
+        self.insert_str_with(idx, string, &infos);
     }
 
     /// Push a char to the underlying text.
     pub fn push(&mut self, ch: char) {
-        self.root.push(ch);
+        self.insert(self.length(), ch);
     }
 
     /// Push a string to the underlying text.
     pub fn push_str(&mut self, string: &str) {
-        self.root.push_str(string);
+        self.insert_str(self.length(), string);
+    }
+
+    /// Get the underlying text.
+    pub fn text(&self) -> String {
+        self.root.text()
     }
 
     /// Get the length of the underlying text.
@@ -63,7 +190,65 @@ impl<T> Tree<T>
 
     /// Remove a count of characters from the underlying text starting at idx.
     pub fn remove(&mut self, idx: usize, count: usize) {
-        self.root.remove(idx, count);
+        assert!(idx + count <= self.length());
+
+        self.pending = None;
+        Shared::make_mut(&mut self.root).remove_in(idx, count, &mut change::Context::new(&self.subscriptions, self.ids.as_mut()));
+
+        if count > 0 {
+            self.subscriptions.emit(change::Event::TextRemoved { idx, len: count });
+        }
+    }
+
+    /// Replace the text in the passed range by the passed string.
+    /// The range is the passed start index (inclusive) to the passed end index (exclusive).
+    /// The string will carry the infos of the first replaced character or, when the range is empty,
+    /// the infos it would carry when inserted.
+    pub fn replace(&mut self, start_idx: usize, end_idx: usize, string: &str) {
+        assert!(start_idx <= end_idx);
+
+        if start_idx == end_idx {
+            self.insert_str(start_idx, string);
+        } else {
+            let infos = self.root.infos_at(start_idx);
+            self.replace_with(start_idx, end_idx, string, &infos);
+        }
+    }
+
+    /// Replace the text in the passed range by the passed string carrying exactly the passed syntax/format infos.
+    /// The range is the passed start index (inclusive) to the passed end index (exclusive).
+    pub fn replace_with_infos(&mut self, start_idx: usize, end_idx: usize, string: &str, infos: Vec<T>) {
+        assert!(start_idx <= end_idx);
+
+        let infos: Vec<Shared<T>> = infos.into_iter().map(Shared::new).collect();
+        self.replace_with(start_idx, end_idx, string, &infos);
+    }
+
+    /// Move the text in the passed range along with its syntax/format infos to the passed destination index.
+    /// The range is the passed start index (inclusive) to the passed end index (exclusive).
+    /// The destination index refers to the text before moving and must not lie within the range.
+    pub fn move_range(&mut self, start_idx: usize, end_idx: usize, dest_idx: usize) {
+        assert!(start_idx <= end_idx);
+        assert!(dest_idx <= start_idx || dest_idx >= end_idx);
+
+        if start_idx == end_idx || dest_idx == start_idx || dest_idx == end_idx {
+            return;
+        }
+
+        let spans = self.relative_spans(start_idx, end_idx);
+        let mut text = String::new();
+        for span in &spans {
+            text.push_str(&span.text);
+        }
+
+        // Listeners are notified about the move only instead of the intermediate removal and insertion
+        self.pending = None;
+        Shared::make_mut(&mut self.root).remove_in(start_idx, end_idx - start_idx, &mut change::Context::new(&self.subscriptions, self.ids.as_mut()));
+
+        let insert_idx = if dest_idx > end_idx { dest_idx - (end_idx - start_idx) } else { dest_idx };
+        self.insert_text(insert_idx, &text, &spans);
+
+        self.subscriptions.emit(change::Event::TextMoved { start: start_idx, end: end_idx, dest: dest_idx });
     }
 
     /// Pop a char from the underlying text.
@@ -74,11 +259,134 @@ impl<T> Tree<T>
     /// Clear the underlying text.
     /// Specify whether you want the tree to keep the formats on the root node.
     pub fn clear(&mut self, keep_formats: bool) {
-        self.root.remove(0, self.length());
+        let length = self.length();
 
         if !keep_formats {
-            self.root.clear_infos();
+            let infos: Vec<Shared<T>> = self.root.infos().map(Shared::clone).collect();
+            Shared::make_mut(&mut self.root).clear_infos();
+
+            // Infos of the root are removed from the text before it is removed
+            if length > 0 {
+                for info in infos {
+                    self.subscriptions.emit(change::Event::InfoRemoved { start: 0, end: length, info: &info });
+                }
+            }
         }
+
+        self.remove(0, length);
+    }
+
+    /// Get a new tree holding the text and effective syntax/format infos of the passed range.
+    /// The range is the passed start index (inclusive) to the passed end index (exclusive).
+    /// The new tree is in canonical shape (see `normalize`).
+    pub fn slice(&self, start_idx: usize, end_idx: usize) -> Tree<T>
+        where T: Ord {
+        assert!(start_idx <= end_idx);
+        assert!(end_idx <= self.length());
+
+        let mut tree = Tree::new("", None);
+        let root = Node::new_root_from_spans(&span::merge_adjacent(self.spans(start_idx, end_idx)), &mut change::Context::new(&tree.subscriptions, tree.ids.as_mut()));
+        tree.root = Shared::new(root);
+        tree
+    }
+
+    /// Insert the text of the passed tree along with its syntax/format infos.
+    /// The inserted text is merged with surrounding text carrying the same infos.
+    pub fn insert_tree(&mut self, idx: usize, tree: &Tree<T>) {
+        self.pending = None;
+        self.insert_spans(idx, &tree.spans(0, tree.length()));
+    }
+
+    /// Get the operations turning this tree into the passed tree.
+    /// The text is changed between the common prefix and suffix of both texts,
+    /// the formatting by setting and unsetting infos on ranges afterwards.
+    pub fn diff(&self, other: &Tree<T>) -> Vec<diff::Operation<T>>
+        where T: Clone {
+        self.diff_shared(other).into_iter()
+            .map(|operation| operation.map_info(|info| T::clone(&info)))
+            .collect()
+    }
+
+    /// Apply the passed operations in order.
+    pub fn apply(&mut self, operations: &[diff::Operation<T>])
+        where T: Clone {
+        let operations: Vec<diff::Operation<Shared<T>>> = operations.iter()
+            .map(|operation| operation.clone().map_info(Shared::new))
+            .collect();
+        self.apply_shared(&operations);
+    }
+
+    /// Rebuild the tree into a canonical shape for its text and effective syntax/format infos.
+    /// Semantically equal trees are structurally equal after being normalized.
+    pub fn normalize(&mut self)
+        where T: Ord {
+        Shared::make_mut(&mut self.root).normalize_in(&mut change::Context::new(&self.subscriptions, self.ids.as_mut()));
+    }
+
+    /// Visit all nodes in pre order with the passed visitor allowed to change infos and leaf text.
+    /// Afterwards the changes are applied to the tree emitting the usual text and info change events
+    /// and the tree is normalized.
+    pub fn walk_mut<F>(&mut self, mut visitor: F)
+        where T: Ord, F: FnMut(&mut visit::NodeMut<T>) {
+        let mut edited = Tree::new("", None);
+        edited.root = Shared::clone(&self.root);
+        Shared::make_mut(&mut edited.root).walk_mut(0, &mut Vec::new(), &mut visitor);
+
+        let operations = self.diff_shared(&edited);
+        self.apply_shared(&operations);
+        self.normalize();
+    }
+
+    /// Create a new tree with the same text and the infos transformed by the passed function.
+    /// Infos the function returns `None` for are dropped and neighbors ending up with equal infos are merged.
+    /// The new tree has no listeners subscribed and the default expand behavior.
+    pub fn map_infos<U, F>(&self, mut f: F) -> Tree<U>
+        where U: Eq + Hash + Ord, F: FnMut(&T) -> Option<U> {
+        let mut mapped: HashMap<Shared<T>, Option<Shared<U>>> = HashMap::new();
+        self.map_shared(|info| {
+            mapped.entry(Shared::clone(info))
+                .or_insert_with(|| f(info).map(Shared::new))
+                .clone()
+        })
+    }
+
+    /// Create a new tree with the same text keeping only the infos the passed predicate returns true for.
+    /// Neighbors ending up with equal infos are merged.
+    /// The new tree has no listeners subscribed and the default expand behavior.
+    pub fn filter_infos<F>(&self, mut predicate: F) -> Tree<T>
+        where T: Ord, F: FnMut(&T) -> bool {
+        self.map_shared(|info| if predicate(info) { Some(Shared::clone(info)) } else { None })
+    }
+
+    /// Check the structural invariants of the tree.
+    /// Returns all violations found (empty if the tree is consistent).
+    pub fn validate(&self) -> Vec<validation::Violation<T>> {
+        self.root.validate()
+    }
+
+    /// Check whether the passed tree has the same text and effective formatting as this tree,
+    /// regardless of how the nodes of both trees are structured.
+    pub fn semantically_eq(&self, other: &Tree<T>) -> bool {
+        let spans = self.flat_spans();
+        let other_spans = other.flat_spans();
+
+        spans.len() == other_spans.len()
+            && spans.iter().zip(&other_spans).all(|(a, b)| a.text == b.text && a.has_same_infos(b))
+    }
+
+    /// Get a hash of the text and effective formatting of the tree.
+    /// Semantically equal trees have the same content hash.
+    pub fn content_hash(&self) -> u64
+        where T: Ord {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Get an immutable snapshot of the tree.
+    /// The snapshot shares its nodes with the tree until they are changed.
+    pub fn snapshot(&self) -> Snapshot<T> {
+        Snapshot::new(Shared::clone(&self.root))
     }
 
     /// Get the root node.
@@ -86,15 +394,355 @@ impl<T> Tree<T>
         &self.root
     }
 
+    /// Find the node with the passed ID.
+    pub fn find_node(&self, id: id::NodeId) -> Option<&Node<T>> {
+        self.root.find(id).map(|item| item.node)
+    }
+
+    /// Get the indices of the children to descend to from the root to reach the node with the passed ID.
+    pub fn path_of(&self, id: id::NodeId) -> Option<Vec<usize>> {
+        self.root.find(id).map(|item| item.path.to_vec())
+    }
+
+    /// Get the absolute start offset of the text of the node with the passed ID.
+    pub fn offset_of(&self, id: id::NodeId) -> Option<usize> {
+        self.root.find(id).map(|item| item.start)
+    }
+
+    /// Get an iterator over the ancestors of the node with the passed ID from its parent up to the root.
+    pub fn ancestors(&self, id: id::NodeId) -> Option<iterator::Ancestors<T>> {
+        self.root.ancestors(id)
+    }
+
     /// Get a depth first pre order iterator.
     pub fn pre_order_iter(&self) -> iterator::PreOrder<T> {
         self.root.pre_order_iter()
     }
 
+    /// Get a depth first post order iterator.
+    pub fn post_order_iter(&self) -> iterator::PostOrder<T> {
+        self.root.post_order_iter()
+    }
+
+    /// Get a depth first reverse pre order iterator.
+    pub fn reverse_pre_order_iter(&self) -> iterator::ReversePreOrder<T> {
+        self.root.reverse_pre_order_iter()
+    }
+
+    /// Get a breadth first iterator.
+    pub fn breadth_first_iter(&self) -> iterator::BreadthFirst<T> {
+        self.root.breadth_first_iter()
+    }
+
+    /// Get a depth first pre order iterator over the nodes intersecting the passed range.
+    /// The range is the passed start index (inclusive) to the passed end index (exclusive).
+    /// Subtrees outside the range are skipped and the items are clipped to the range (see `iterator::Clipped`).
+    pub fn iter_range(&self, start_idx: usize, end_idx: usize) -> iterator::Range<T> {
+        assert!(start_idx <= end_idx);
+        assert!(end_idx <= self.length());
+
+        self.root.iter_range(start_idx, end_idx)
+    }
+
     /// Get a leaf iterator.
     pub fn leaf_iter(&self) -> impl Iterator<Item=iterator::Item<T>> {
         self.root.leaf_iter()
     }
+
+    /// Replace the text in the passed range by the passed string carrying exactly the passed infos.
+    /// The string is inserted before removing the replaced text to keep the surrounding nodes intact.
+    /// Listeners are told about a single replacement instead of an insertion followed by a removal.
+    fn replace_with(&mut self, start_idx: usize, end_idx: usize, string: &str, infos: &[Shared<T>]) {
+        self.pending = None;
+
+        if start_idx == end_idx {
+            self.insert_str_with(start_idx, string, infos);
+            return;
+        } else if string.is_empty() {
+            self.remove(start_idx, end_idx - start_idx);
+            return;
+        }
+
+        let spans = [Span {
+            start: 0,
+            end: string.len(),
+            text: String::from(string),
+            infos: infos.to_vec(),
+        }];
+        self.insert_text(start_idx, string, &spans);
+        Shared::make_mut(&mut self.root).remove_in(start_idx + string.len(), end_idx - start_idx, &mut change::Context::new(&self.subscriptions, self.ids.as_mut()));
+
+        self.subscriptions.emit(change::Event::TextReplaced { start: start_idx, end: end_idx, text: string });
+        self.emit_infos_applied(start_idx, &spans);
+    }
+
+    /// Get the operations turning this tree into the passed tree sharing the infos of the passed tree.
+    /// See `diff` for details.
+    fn diff_shared(&self, other: &Tree<T>) -> Vec<diff::Operation<Shared<T>>> {
+        let text = self.text();
+        let other_text = other.text();
+        let (prefix, suffix) = diff::common_affixes(&text, &other_text);
+        let removed = text.len() - prefix - suffix;
+        let inserted = &other_text[prefix..other_text.len() - suffix];
+
+        let mut operations = Vec::new();
+        if removed > 0 {
+            operations.push(diff::Operation::Delete { idx: prefix, len: removed });
+        }
+        if !inserted.is_empty() {
+            operations.push(diff::Operation::Insert { idx: prefix, text: String::from(inserted) });
+        }
+
+        // Formatting after the text has been changed
+        let mut spans = self.spans(0, prefix);
+        if !inserted.is_empty() {
+            spans.push(Span {
+                start: prefix,
+                end: prefix + inserted.len(),
+                text: String::from(inserted),
+                infos: Vec::new(),
+            });
+        }
+        for mut span in self.relative_spans(text.len() - suffix, text.len()) {
+            span.start += prefix + inserted.len();
+            span.end += prefix + inserted.len();
+            spans.push(span);
+        }
+
+        let (to_unset, to_set) = span::info_changes(&spans, &other.spans(0, other_text.len()));
+        for (info, start, end) in to_unset {
+            operations.push(diff::Operation::Unset { start, end, info });
+        }
+        for (info, start, end) in to_set {
+            operations.push(diff::Operation::Set { start, end, info });
+        }
+
+        operations
+    }
+
+    /// Apply the passed operations sharing their infos in order.
+    fn apply_shared(&mut self, operations: &[diff::Operation<Shared<T>>]) {
+        self.pending = None;
+
+        for operation in operations {
+            match operation {
+                diff::Operation::Insert { idx, text } => self.insert_str_with(*idx, text, &[]),
+                diff::Operation::Delete { idx, len } => self.remove(*idx, *len),
+                diff::Operation::Set { start, end, info } => self.set_shared(*start, *end, Shared::clone(info)),
+                diff::Operation::Unset { start, end, info } => self.unset_shared(*start, *end, Shared::clone(info)),
+            }
+        }
+    }
+
+    /// Insert a string carrying exactly the passed syntax/format infos.
+    fn insert_str_with(&mut self, idx: usize, string: &str, infos: &[Shared<T>]) {
+        self.insert_spans(idx, &[Span {
+            start: 0,
+            end: string.len(),
+            text: String::from(string),
+            infos: infos.to_vec(),
+        }]);
+    }
+
+    /// Insert the text of the passed spans carrying exactly their syntax/format infos.
+    fn insert_spans(&mut self, idx: usize, spans: &[Span<T>]) {
+        let mut text = String::new();
+        for span in spans {
+            text.push_str(&span.text);
+        }
+
+        if text.is_empty() {
+            return;
+        }
+
+        self.insert_text(idx, &text, spans);
+
+        self.subscriptions.emit(change::Event::TextInserted { idx, text: &text });
+        self.emit_infos_applied(idx, spans);
+    }
+
+    /// Insert the passed text (made up of the passed spans) without emitting range events.
+    fn insert_text(&mut self, idx: usize, text: &str, spans: &[Span<T>]) {
+        Shared::make_mut(&mut self.root).insert_str_in(idx, text, &mut change::Context::new(&self.subscriptions, self.ids.as_mut()));
+
+        // The leaf taking the text might already carry exactly its infos (for example when typing)
+        if spans.len() == 1 {
+            let span = &spans[0];
+            let infos = self.root.infos_at(idx);
+            if infos.len() == span.infos.len() && infos.iter().all(|info| span.infos.contains(info)) {
+                return;
+            }
+        }
+
+        self.reformat(idx, spans);
+    }
+
+    /// Emit `InfoApplied` events for the infos of the passed spans inserted at the passed index.
+    fn emit_infos_applied(&self, idx: usize, spans: &[Span<T>]) {
+        let mut ranges: Vec<(Shared<T>, usize, usize)> = Vec::new();
+        for span in spans {
+            for info in &span.infos {
+                span::add_range(&mut ranges, info, idx + span.start, idx + span.end);
+            }
+        }
+        for (info, start, end) in ranges {
+            self.subscriptions.emit(change::Event::InfoApplied { start, end, info: &info });
+        }
+    }
+
+    /// Check whether infos have been toggled for the passed caret index.
+    fn is_pending_at(&self, idx: usize) -> bool {
+        match &self.pending {
+            Some(pending) => pending.idx == idx,
+            None => false,
+        }
+    }
+
+    /// Get the infos toggled for the passed caret index.
+    /// Infos toggled for another index are dropped.
+    fn pending_at(&mut self, idx: usize) -> &mut Pending<T> {
+        if !self.is_pending_at(idx) {
+            self.pending = Some(Pending {
+                idx,
+                added: Vec::new(),
+                removed: Vec::new(),
+            });
+        }
+
+        self.pending.as_mut().unwrap()
+    }
+
+    /// Get the syntax/format infos text inserted at the passed index should carry.
+    fn expanded_infos(&self, idx: usize) -> Vec<Shared<T>> {
+        let length = self.length();
+        let left = if idx > 0 { self.root.infos_at(idx - 1) } else { Vec::new() };
+        let right = if idx < length { self.root.infos_at(idx) } else { Vec::new() };
+
+        // Infos of the root node apply to the whole text
+        let mut result: Vec<Shared<T>> = self.root.infos().map(Shared::clone).collect();
+
+        for info in left {
+            if !result.contains(&info) && (right.contains(&info) || self.expand_of(&info) == Expand::InclusiveEnd) {
+                result.push(info);
+            }
+        }
+        for info in right {
+            if !result.contains(&info) && self.expand_of(&info) == Expand::InclusiveStart {
+                result.push(info);
+            }
+        }
+
+        result
+    }
+
+    /// Get the expand behavior of the passed info.
+    fn expand_of(&self, info: &T) -> Expand {
+        match &self.expand_behavior {
+            Some(f) => f(info),
+            None => Expand::default(),
+        }
+    }
+
+    /// Get the spans of text with the same effective syntax/format infos in the passed range.
+    fn spans(&self, start_idx: usize, end_idx: usize) -> Vec<Span<T>> {
+        let mut result = Vec::new();
+        if start_idx < end_idx {
+            self.root.collect_spans(start_idx, end_idx, 0, &[], &mut result);
+        }
+        result
+    }
+
+    /// Get the spans of the whole text with adjacent spans of the same infos merged.
+    fn flat_spans(&self) -> Vec<Span<T>> {
+        span::merge_adjacent(self.spans(0, self.length()))
+    }
+
+    /// Create a new tree with the same text and the infos transformed by the passed function.
+    fn map_shared<U, F>(&self, mut f: F) -> Tree<U>
+        where U: Eq + Hash + Ord, F: FnMut(&Shared<T>) -> Option<Shared<U>> {
+        fn map_all<T, U, F>(infos: &[Shared<T>], f: &mut F) -> Vec<Shared<U>>
+            where U: Eq, F: FnMut(&Shared<T>) -> Option<Shared<U>> {
+            let mut result = Vec::with_capacity(infos.len());
+            for info in infos {
+                if let Some(info) = f(info) {
+                    if !result.contains(&info) {
+                        result.push(info);
+                    }
+                }
+            }
+            result
+        }
+
+        let spans: Vec<Span<U>> = self.flat_spans().into_iter().map(|span| Span {
+            start: span.start,
+            end: span.end,
+            text: span.text,
+            infos: map_all(&span.infos, &mut f),
+        }).collect();
+
+        let mut tree = Tree::new("", None);
+        let mut root = Node::new_root_from_spans(&span::merge_adjacent(spans), &mut change::Context::new(&tree.subscriptions, tree.ids.as_mut()));
+        if root.length() == 0 {
+            let infos: Vec<Shared<T>> = self.root.infos().cloned().collect();
+            for info in map_all(&infos, &mut f) {
+                root.add_info(info);
+            }
+        }
+
+        tree.root = Shared::new(root);
+        tree
+    }
+
+    /// Get the spans of text with the same effective syntax/format infos in the passed range
+    /// with span indices relative to the start of the range.
+    fn relative_spans(&self, start_idx: usize, end_idx: usize) -> Vec<Span<T>> {
+        let mut spans = self.spans(start_idx, end_idx);
+        for span in &mut spans {
+            span.start -= start_idx;
+            span.end -= start_idx;
+        }
+        spans
+    }
+
+    /// Set the passed shared syntax/format info for the passed (non-empty) range.
+    /// Nothing happens when the whole range already carries the info.
+    fn set_shared(&mut self, start_idx: usize, end_idx: usize, info: Shared<T>) {
+        assert!(start_idx < end_idx);
+
+        if self.root.has_info_throughout(start_idx, end_idx, &info) {
+            return;
+        }
+
+        Shared::make_mut(&mut self.root).set_in(start_idx, end_idx, Shared::clone(&info), &mut change::Context::new(&self.subscriptions, self.ids.as_mut()));
+        self.subscriptions.emit(change::Event::InfoApplied { start: start_idx, end: end_idx, info: &info });
+    }
+
+    /// Unset the passed shared syntax/format info for the passed (non-empty) range.
+    /// Nothing happens when no text in the range carries the info.
+    fn unset_shared(&mut self, start_idx: usize, end_idx: usize, info: Shared<T>) {
+        assert!(start_idx < end_idx);
+
+        if !self.root.has_info_within(start_idx, end_idx, &info) {
+            return;
+        }
+
+        Shared::make_mut(&mut self.root).unset_in(start_idx, end_idx, Shared::clone(&info), &mut change::Context::new(&self.subscriptions, self.ids.as_mut()));
+        self.subscriptions.emit(change::Event::InfoRemoved { start: start_idx, end: end_idx, info: &info });
+    }
+
+    /// Set and unset syntax/format infos so that the text starting at the passed index
+    /// carries exactly the infos of the passed spans (with span indices relative to the index).
+    fn reformat(&mut self, idx: usize, spans: &[Span<T>]) {
+        let length = spans.last().map_or(0, |s| s.end);
+        let (to_unset, to_set) = span::info_changes(&self.relative_spans(idx, idx + length), spans);
+
+        for (info, start, end) in to_unset {
+            Shared::make_mut(&mut self.root).unset_in(idx + start, idx + end, info, &mut change::Context::new(&self.subscriptions, self.ids.as_mut()));
+        }
+        for (info, start, end) in to_set {
+            Shared::make_mut(&mut self.root).set_in(idx + start, idx + end, info, &mut change::Context::new(&self.subscriptions, self.ids.as_mut()));
+        }
+    }
 }
 
 impl<T> fmt::Debug for Tree<T>
@@ -103,3 +751,28 @@ impl<T> fmt::Debug for Tree<T>
         write!(f, "{:?}", self.root)
     }
 }
+
+impl<T> PartialEq for Tree<T>
+    where T: Eq + Hash {
+    /// Compare the node structure of both trees (ignoring node IDs).
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root
+    }
+}
+
+impl<T> Eq for Tree<T>
+    where T: Eq + Hash {}
+
+impl<T> Hash for Tree<T>
+    where T: Ord + Hash {
+    /// Hash the text and effective formatting independent of the node structure.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for span in self.flat_spans() {
+            span.text.hash(state);
+
+            let mut sorted_infos: Vec<&Shared<T>> = span.infos.iter().collect();
+            sorted_infos.sort();
+            sorted_infos.hash(state);
+        }
+    }
+}
@@ -0,0 +1,3 @@
+mod violation;
+
+pub use violation::Violation;
@@ -0,0 +1,57 @@
+use crate::info::Shared;
+use crate::id::NodeId;
+
+/// Violation of a structural invariant of a tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation<T> {
+    /// A node with children also holds text.
+    TextOnInnerNode {
+        node_id: NodeId,
+    },
+    /// A node has an empty list of children instead of none.
+    EmptyChildren {
+        node_id: NodeId,
+    },
+    /// A leaf node holds no text.
+    MissingText {
+        node_id: NodeId,
+    },
+    /// A leaf node other than an empty root holds empty text.
+    EmptyLeaf {
+        node_id: NodeId,
+    },
+    /// A node other than the root has children but no infos,
+    /// so its children belong into its parent instead.
+    InnerNodeWithoutInfos {
+        node_id: NodeId,
+    },
+    /// A node has a single child which should have been merged into it.
+    SingleChild {
+        node_id: NodeId,
+    },
+    /// The cached length of a node differs from the length of the text under it.
+    WrongLength {
+        node_id: NodeId,
+        cached: usize,
+        actual: usize,
+    },
+    /// A node repeats an info already set on one of its ancestors.
+    RedundantInfo {
+        node_id: NodeId,
+        info: Shared<T>,
+    },
+    /// The children at the index and the index + 1 could be merged,
+    /// since they share an info or are leaves with equal infos.
+    UnmergedSiblings {
+        parent_id: NodeId,
+        idx: usize,
+    },
+    /// The root node is not flagged as root.
+    MissingRootFlag {
+        node_id: NodeId,
+    },
+    /// A node other than the root is flagged as root.
+    MisplacedRootFlag {
+        node_id: NodeId,
+    },
+}
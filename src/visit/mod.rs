@@ -0,0 +1,3 @@
+mod node_mut;
+
+pub use node_mut::NodeMut;
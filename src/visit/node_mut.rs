@@ -0,0 +1,78 @@
+use crate::Node;
+use crate::info::Shared;
+use std::collections::hash_set::Iter;
+use std::hash::Hash;
+
+/// Node visited by a mutable visitor.
+/// Allows changing the infos of the node and the text of a leaf, but not the structure of the tree.
+pub struct NodeMut<'a, T> {
+    node: &'a mut Node<T>,
+    level: usize,
+    path: &'a [usize],
+}
+
+impl<'a, T> NodeMut<'a, T>
+    where T: Eq + Hash {
+    pub(crate) fn new(node: &'a mut Node<T>, level: usize, path: &'a [usize]) -> Self {
+        NodeMut {
+            node,
+            level,
+            path,
+        }
+    }
+
+    /// Get the node as it currently is.
+    pub fn node(&self) -> &Node<T> {
+        self.node
+    }
+
+    /// Get the depth of the node in the tree.
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Get the indices of the children to descend to from the root to reach the node.
+    pub fn path(&self) -> &[usize] {
+        self.path
+    }
+
+    /// Check whether the node is a leaf.
+    pub fn is_leaf(&self) -> bool {
+        self.node.is_leaf()
+    }
+
+    /// Get iterator over all infos the node has.
+    pub fn infos(&self) -> Iter<Shared<T>> {
+        self.node.infos()
+    }
+
+    /// Add the passed info to the node.
+    pub fn add_info(&mut self, info: T) {
+        self.node.add_info(Shared::new(info));
+    }
+
+    /// Remove the passed info from the node.
+    /// Returns whether the node had the info.
+    pub fn remove_info(&mut self, info: &T) -> bool {
+        self.node.infos_mut().remove(info)
+    }
+
+    /// Keep only the infos of the node the passed predicate returns true for.
+    pub fn retain_infos<F>(&mut self, mut predicate: F)
+        where F: FnMut(&T) -> bool {
+        self.node.infos_mut().retain(|info| predicate(info));
+    }
+
+    /// Get the text of the node (or its children).
+    pub fn text(&self) -> String {
+        self.node.text()
+    }
+
+    /// Replace the text of the leaf.
+    /// Panics when the node is not a leaf.
+    pub fn set_text(&mut self, text: String) {
+        assert!(self.node.is_leaf());
+
+        self.node.set_text(text);
+    }
+}
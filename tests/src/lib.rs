@@ -1,6 +1,17 @@
+#[cfg(test)]
+mod properties;
+
 #[cfg(test)]
 mod tests {
     use syntax_tree::{Tree, Node};
+    use syntax_tree::info::{Expand, Shared};
+    use syntax_tree::change::{self, Event, OwnedEvent};
+    use syntax_tree::diff::Operation;
+    use syntax_tree::validation::Violation;
+    use syntax_tree::id::{self, NodeId};
+    use syntax_tree::iterator::Item;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
     use shared::info::FontStyle;
 
     #[test]
@@ -258,7 +269,7 @@ mod tests {
         tree.set(4, 7, FontStyle::Underline);
         tree.set(4, "Hello World".len(), FontStyle::Bold);
         tree.set(0, 4, FontStyle::Underline);
-        tree.remove(7, "Hello World".len());
+        tree.remove(7, "Hello World".len() - 7);
 
         assert_eq!(format!("{:#?}", tree), "|-- 'Hello W' [Underline]
     |-- 'Hell' []
@@ -266,6 +277,14 @@ mod tests {
 ")
     }
 
+    #[test]
+    #[should_panic]
+    fn remove_test_out_of_range() {
+        let mut tree: Tree<FontStyle> = Tree::new("Hello World", None);
+        tree.set(4, 7, FontStyle::Underline);
+        tree.remove(7, "Hello World".len());
+    }
+
     #[test]
     fn remove_test_regroup_2() {
         let mut tree: Tree<FontStyle> = Tree::new("Hello World", None);
@@ -451,4 +470,1370 @@ mod tests {
     |-- 'orld' [Bold]
 ");
     }
+
+    #[test]
+    fn insert_expand_test_default() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.insert_str(5, "!!");
+        tree.insert_str(0, "> ");
+
+        assert_eq!(format!("{:#?}", tree), "|-- '> Hello!! World' []
+    |-- '> ' []
+    |-- 'Hello!!' [Bold]
+    |-- ' World' []
+");
+    }
+
+    #[test]
+    fn insert_expand_test_exclusive() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set_expand_behavior(Box::new(|_| Expand::Exclusive));
+        tree.set(0, 5, FontStyle::Bold);
+        tree.insert(5, '!');
+        tree.insert(2, '-');
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'He-llo! World' []
+    |-- 'He-llo' [Bold]
+    |-- '! World' []
+");
+    }
+
+    #[test]
+    fn insert_expand_test_inclusive_start() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set_expand_behavior(Box::new(|info| match info {
+            FontStyle::Bold => Expand::InclusiveStart,
+            _ => Expand::InclusiveEnd,
+        }));
+        tree.set(6, "Hello World".len(), FontStyle::Bold);
+        tree.set(0, 5, FontStyle::Italic);
+        tree.insert_str(5, "_");
+        tree.insert_str(7, "_");
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hello_ _World' []
+    |-- 'Hello_' [Italic]
+    |-- ' ' []
+    |-- '_World' [Bold]
+");
+    }
+
+    #[test]
+    fn insert_str_with_infos_test() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.insert_str_with_infos(5, " you", vec!(FontStyle::Italic));
+        tree.insert_str_with_infos(0, "Oh ", vec!(FontStyle::Bold));
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'Oh Hello you World' []
+    |-- 'Oh Hello' [Bold]
+    |-- ' you' [Italic]
+    |-- ' World' []
+");
+    }
+
+    #[test]
+    fn insert_char_at_leaf_boundary() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.insert(5, '!');
+        tree.push('?');
+
+        assert_eq!(tree.get_root().text(), "Hello! World?");
+    }
+
+    #[test]
+    fn pending_infos_test_set() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(5, 5, FontStyle::Bold);
+        tree.insert_str(5, " dear");
+        tree.insert(10, ',');
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hello dear, World' []
+    |-- 'Hello' []
+    |-- ' dear,' [Bold]
+    |-- ' World' []
+");
+    }
+
+    #[test]
+    fn pending_infos_test_unset() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set(0, 5, FontStyle::Italic);
+        tree.unset(5, 5, FontStyle::Bold);
+        tree.insert_str(5, "!");
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hello! World' []
+    |-- 'Hello!' [Italic]
+        |-- 'Hello' [Bold]
+        |-- '!' []
+    |-- ' World' []
+");
+    }
+
+    #[test]
+    fn pending_infos_test_tree_changed_after_toggle() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(5, 5, FontStyle::Bold);
+        tree.set(0, 5, FontStyle::Italic);
+        tree.insert_str(5, "!");
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hello! World' []
+    |-- 'Hello!' [Italic]
+        |-- 'Hello' []
+        |-- '!' [Bold]
+    |-- ' World' []
+");
+    }
+
+    #[test]
+    fn pending_infos_test_cleared_on_caret_move() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(5, 5, FontStyle::Bold);
+        assert_eq!(tree.pending_infos(5).unwrap().len(), 1);
+
+        tree.move_caret(5);
+        assert!(tree.pending_infos(5).is_some());
+
+        tree.move_caret(6);
+        assert!(tree.pending_infos(5).is_none());
+
+        tree.insert_str(5, "!");
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hello! World' []
+");
+    }
+
+    #[test]
+    fn slice_test_1() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(4, 7, FontStyle::Underline);
+        tree.set(6, "Hello World".len(), FontStyle::Bold);
+        tree.set(6, 9, FontStyle::Underline);
+
+        let slice = tree.slice(5, 10);
+        assert_eq!(slice.get_root().text(), " Worl");
+        assert_eq!(format!("{:#?}", slice), "|-- ' Worl' []
+    |-- ' Wor' [Underline]
+        |-- ' ' []
+        |-- 'Wor' [Bold]
+    |-- 'l' [Bold]
+");
+    }
+
+    #[test]
+    fn slice_test_2() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, "Hello World".len(), FontStyle::Italic);
+        tree.set(6, "Hello World".len(), FontStyle::Bold);
+        tree.set(4, 7, FontStyle::Underline);
+
+        assert_eq!(format!("{:#?}", tree.slice(7, 9)), "|-- 'or' [Bold, Italic]
+");
+        assert_eq!(format!("{:#?}", tree.slice(3, 3)), "|-- '' []
+");
+    }
+
+    #[test]
+    fn slice_test_normalized() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 2, FontStyle::Bold);
+        tree.set(4, 6, FontStyle::Bold);
+        tree.set(1, 5, FontStyle::Italic);
+        tree.set(8, 11, FontStyle::Bold);
+
+        let slice = tree.slice(0, 10);
+        let mut normalized = tree.slice(0, 10);
+        normalized.normalize();
+
+        assert_eq!(format!("{:#?}", slice), format!("{:#?}", normalized));
+        assert!(slice.validate().is_empty());
+    }
+
+    #[test]
+    fn insert_tree_test_1() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(6, "Hello World".len(), FontStyle::Bold);
+
+        let mut fragment = Tree::new("dear ", None);
+        fragment.set(0, 4, FontStyle::Italic);
+
+        tree.insert_tree(6, &fragment);
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hello dear World' []
+    |-- 'Hello ' []
+    |-- 'dear' [Italic]
+    |-- ' ' []
+    |-- 'World' [Bold]
+");
+    }
+
+    #[test]
+    fn insert_tree_test_merge() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+
+        let mut fragment = Tree::new("!!", None);
+        fragment.set(0, 2, FontStyle::Bold);
+        fragment.set(1, 2, FontStyle::Underline);
+
+        tree.insert_tree(5, &fragment);
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hello!! World' []
+    |-- 'Hello!!' [Bold]
+        |-- 'Hello!' []
+        |-- '!' [Underline]
+    |-- ' World' []
+");
+    }
+
+    #[test]
+    fn slice_and_insert_tree_test() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Italic);
+        tree.set(2, 4, FontStyle::Bold);
+
+        let copy = tree.slice(0, 5);
+        tree.insert_tree(tree.length(), &copy);
+        assert_eq!(tree.get_root().text(), "Hello WorldHello");
+        assert_eq!(format!("{:#?}", tree.slice(11, 16)), format!("{:#?}", copy));
+    }
+
+    #[test]
+    fn replace_test_keeps_formatting() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(6, "Hello World".len(), FontStyle::Bold);
+        tree.set(6, 9, FontStyle::Italic);
+        tree.replace(6, 9, "Wo");
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hello Wold' []
+    |-- 'Hello ' []
+    |-- 'Wold' [Bold]
+        |-- 'Wo' [Italic]
+        |-- 'ld' []
+");
+    }
+
+    #[test]
+    fn replace_test_whole_text() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.replace(0, tree.length(), "Bye");
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'Bye' [Bold]
+");
+    }
+
+    #[test]
+    fn replace_with_infos_test() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.replace_with_infos(4, 7, "o, w", vec!(FontStyle::Italic));
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hello, world' []
+    |-- 'Hell' [Bold]
+    |-- 'o, w' [Italic]
+    |-- 'orld' []
+");
+    }
+
+    #[test]
+    fn move_range_test_forward() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set(1, 3, FontStyle::Italic);
+        tree.move_range(0, 6, "Hello World".len());
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'WorldHello ' []
+    |-- 'World' []
+    |-- 'Hello' [Bold]
+        |-- 'H' []
+        |-- 'el' [Italic]
+        |-- 'lo' []
+    |-- ' ' []
+");
+    }
+
+    #[test]
+    fn move_range_test_backward() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(6, "Hello World".len(), FontStyle::Underline);
+        tree.move_range(6, 8, 0);
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'WoHello rld' []
+    |-- 'Wo' [Underline]
+    |-- 'Hello ' []
+    |-- 'rld' [Underline]
+");
+    }
+
+    #[test]
+    #[should_panic]
+    fn move_range_test_into_itself() {
+        let mut tree: Tree<FontStyle> = Tree::new("Hello World", None);
+        tree.move_range(2, 6, 4);
+    }
+
+    #[test]
+    fn move_range_test_events() {
+        let mut tree: Tree<FontStyle> = Tree::new("Hello World", None);
+        tree.set(6, 8, FontStyle::Underline);
+
+        let (listener, receiver) = change::channel();
+        tree.subscribe(listener);
+        tree.move_range(6, 8, 0);
+        tree.move_range(0, 2, "Hello World".len());
+
+        let events: Vec<OwnedEvent<FontStyle>> = receiver.try_iter().filter(|event| match event {
+            OwnedEvent::TextInserted { .. } | OwnedEvent::TextRemoved { .. } | OwnedEvent::TextReplaced { .. }
+            | OwnedEvent::TextMoved { .. } | OwnedEvent::InfoApplied { .. } | OwnedEvent::InfoRemoved { .. } => true,
+            _ => false,
+        }).collect();
+        assert_eq!(events, vec!(
+            OwnedEvent::TextMoved { start: 6, end: 8, dest: 0 },
+            OwnedEvent::TextMoved { start: 0, end: 2, dest: 11 },
+        ));
+    }
+
+    #[test]
+    fn range_events_test() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+
+        let mut tree = Tree::new("Hello World", Some(Box::new(move |event| {
+            let description = match event {
+                Event::TextInserted { idx, text } => format!("inserted '{}' at {}", text, idx),
+                Event::TextRemoved { idx, len } => format!("removed {} at {}", len, idx),
+                Event::TextReplaced { start, end, text } => format!("replaced {}..{} by '{}'", start, end, text),
+                Event::InfoApplied { start, end, info } => format!("applied {:?} to {}..{}", info, start, end),
+                Event::InfoRemoved { start, end, info } => format!("removed {:?} from {}..{}", info, start, end),
+                _ => return,
+            };
+            recorded.lock().unwrap().push(description);
+        })));
+
+        tree.set(0, 5, FontStyle::Bold);
+        tree.insert_str(5, "!");
+        tree.unset(1, 2, FontStyle::Bold);
+        tree.replace(6, 7, "_");
+        tree.replace(2, 4, "LL");
+        tree.remove(0, 1);
+        tree.set(2, 4, FontStyle::Bold);
+        tree.unset(6, 8, FontStyle::Bold);
+        tree.set(0, 11, FontStyle::Italic);
+        tree.clear(false);
+
+        assert_eq!(*events.lock().unwrap(), vec!(
+            "applied Bold to 0..5",
+            "inserted '!' at 5",
+            "applied Bold to 5..6",
+            "removed Bold from 1..2",
+            "replaced 6..7 by '_'",
+            "replaced 2..4 by 'LL'",
+            "applied Bold to 2..4",
+            "removed 1 at 0",
+            "applied Italic to 0..11",
+            "removed Italic from 0..11",
+            "removed 11 at 0",
+        ));
+    }
+
+    #[test]
+    fn subscribe_test() {
+        let first = Arc::new(Mutex::new(0));
+        let second = Arc::new(Mutex::new(0));
+
+        let mut tree: Tree<FontStyle> = Tree::new("Hello World", None);
+
+        let counter = Arc::clone(&first);
+        let first_id = tree.subscribe(Box::new(move |event| {
+            if let Event::TextInserted { .. } = event {
+                *counter.lock().unwrap() += 1;
+            }
+        }));
+        let counter = Arc::clone(&second);
+        tree.subscribe(Box::new(move |event| {
+            if let Event::TextInserted { .. } = event {
+                *counter.lock().unwrap() += 1;
+            }
+        }));
+
+        tree.set(0, 5, FontStyle::Bold);
+        tree.push('!');
+        assert!(tree.unsubscribe(first_id));
+        assert!(!tree.unsubscribe(first_id));
+        tree.insert(3, '!');
+
+        assert_eq!(*first.lock().unwrap(), 1);
+        assert_eq!(*second.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn subscribe_after_creation_test() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(6, "Hello World".len(), FontStyle::Bold);
+        tree.set(6, 9, FontStyle::Underline);
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+        tree.subscribe(Box::new(move |event| {
+            let description = match event {
+                Event::NodeAdded { parent, added_idx } => format!("added '{}' under '{}'", parent.children()[added_idx].text(), parent.text()),
+                Event::NodeRemoved { parent, removed_idx } => format!("removed {} under '{}'", removed_idx, parent.text()),
+                Event::InfosChanged { node } => format!("infos of '{}' changed", node.text()),
+                _ => return,
+            };
+            recorded.lock().unwrap().push(description);
+        }));
+
+        tree.set(7, 8, FontStyle::Italic);
+
+        assert_eq!(*events.lock().unwrap(), vec!(
+            "added 'W' under 'W'",
+            "added 'o' under 'Wo'",
+            "added 'r' under 'Wor'",
+        ));
+    }
+
+    #[test]
+    fn channel_test() {
+        let (listener, receiver) = change::channel();
+        let indexer = thread::spawn(move || {
+            receiver.iter()
+                .filter(|event| match event {
+                    OwnedEvent::TextInserted { .. } | OwnedEvent::TextRemoved { .. } | OwnedEvent::InfoApplied { .. } => true,
+                    _ => false,
+                })
+                .collect::<Vec<OwnedEvent<FontStyle>>>()
+        });
+
+        let mut tree = Tree::new("Hello World", Some(listener));
+        tree.set(0, 5, FontStyle::Bold);
+        tree.push_str("!");
+        tree.remove(0, 6);
+        drop(tree);
+
+        assert_eq!(indexer.join().unwrap(), vec!(
+            OwnedEvent::InfoApplied { start: 0, end: 5, info: FontStyle::Bold },
+            OwnedEvent::TextInserted { idx: 11, text: String::from("!") },
+            OwnedEvent::TextRemoved { idx: 0, len: 6 },
+        ));
+    }
+
+    #[test]
+    fn owned_event_node_ids_test() {
+        let (listener, receiver) = change::channel();
+        let mut tree = Tree::new("Hello World", Some(listener));
+        tree.set(0, 5, FontStyle::Bold);
+
+        let root_id = tree.get_root().id();
+        let added: Vec<NodeId> = receiver.try_iter()
+            .filter_map(|event| match event {
+                OwnedEvent::NodeAdded { parent_id, node_id, .. } if parent_id == root_id => Some(node_id),
+                _ => None,
+            })
+            .collect();
+
+        let children: Vec<NodeId> = tree.get_root().children().iter().map(|n| n.id()).collect();
+        assert_eq!(added, children);
+    }
+
+    #[test]
+    #[cfg(feature = "sync")]
+    fn sync_test() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+
+        let tree = Arc::new(Mutex::new(tree));
+        let worker_tree = Arc::clone(&tree);
+        thread::spawn(move || {
+            worker_tree.lock().unwrap().set(6, "Hello World".len(), FontStyle::Italic);
+        }).join().unwrap();
+
+        assert_eq!(format!("{:#?}", tree.lock().unwrap()), "|-- 'Hello World' []
+    |-- 'Hello' [Bold]
+    |-- ' ' []
+    |-- 'World' [Italic]
+");
+    }
+
+    #[test]
+    fn snapshot_test() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set(6, "Hello World".len(), FontStyle::Italic);
+
+        let snapshot = tree.snapshot();
+        tree.set(1, 3, FontStyle::Underline);
+        tree.insert_str(5, ",");
+        tree.remove(7, 3);
+
+        assert_eq!(snapshot.text(), "Hello World");
+        assert_eq!(tree.text(), "Hello, ld");
+        assert_eq!(format!("{:#?}", snapshot), "|-- 'Hello World' []
+    |-- 'Hello' [Bold]
+    |-- ' ' []
+    |-- 'World' [Italic]
+");
+        assert_eq!(snapshot.leaf_iter().map(|item| item.node.text()).collect::<Vec<String>>(), vec!("Hello", " ", "World"));
+
+        let unchanged = tree.snapshot();
+        assert_eq!(format!("{:#?}", unchanged.clone()), format!("{:#?}", tree));
+    }
+
+    #[test]
+    fn snapshot_test_shares_unchanged_nodes() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set(6, 11, FontStyle::Italic);
+
+        let snapshot = tree.snapshot();
+        tree.insert_str(8, "x");
+
+        let children = tree.get_root().children();
+        let snapshot_children = snapshot.get_root().children();
+        assert!(Shared::ptr_eq(&children[0], &snapshot_children[0]));
+        assert!(Shared::ptr_eq(&children[1], &snapshot_children[1]));
+        assert!(!Shared::ptr_eq(&children[2], &snapshot_children[2]));
+        assert_eq!(snapshot_children[2].text(), "World");
+    }
+
+    #[test]
+    #[cfg(feature = "sync")]
+    fn sync_snapshot_test() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+
+        let snapshot = tree.snapshot();
+        let renderer = thread::spawn(move || format!("{:#?}", snapshot));
+        tree.remove(0, 6);
+
+        assert_eq!(renderer.join().unwrap(), "|-- 'Hello World' []
+    |-- 'Hello' [Bold]
+    |-- ' World' []
+");
+    }
+
+    #[test]
+    fn diff_test_1() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set(6, 11, FontStyle::Underline);
+
+        let mut other = Tree::new("Hello, World", None);
+        other.set(0, 5, FontStyle::Bold);
+        other.set(7, 12, FontStyle::Italic);
+
+        let operations = tree.diff(&other);
+        assert_eq!(operations.len(), 3);
+        assert_eq!(operations[0], Operation::Insert { idx: 5, text: String::from(",") });
+        assert_eq!(operations[1], Operation::Unset { start: 7, end: 12, info: FontStyle::Underline });
+        assert_eq!(operations[2], Operation::Set { start: 7, end: 12, info: FontStyle::Italic });
+
+        tree.apply(&operations);
+        assert_eq!(format!("{:#?}", tree), format!("{:#?}", other));
+    }
+
+    #[test]
+    fn diff_test_2() {
+        let mut tree = Tree::new("The quick brown fox", None);
+        tree.set(4, 9, FontStyle::Bold);
+        tree.set(10, 19, FontStyle::Italic);
+
+        let mut other = Tree::new("The slow fox", None);
+        other.set(0, 3, FontStyle::Underline);
+        other.set(4, 8, FontStyle::Bold);
+
+        let operations = tree.diff(&other);
+        assert_eq!(operations[0], Operation::Delete { idx: 4, len: 11 });
+        assert_eq!(operations[1], Operation::Insert { idx: 4, text: String::from("slow") });
+
+        tree.apply(&operations);
+        assert_eq!(tree.text(), "The slow fox");
+        assert_eq!(format!("{:#?}", tree), format!("{:#?}", other));
+    }
+
+    #[test]
+    fn diff_test_operations_sent_to_thread() {
+        let mut tree = Tree::new("Hello", None);
+        tree.set(0, 5, FontStyle::Bold);
+
+        let mut other = Tree::new("Hello World", None);
+        other.set(6, 11, FontStyle::Italic);
+
+        let operations = tree.diff(&other);
+        let received = thread::spawn(move || operations.clone()).join().unwrap();
+
+        tree.apply(&received);
+        assert_eq!(format!("{:#?}", tree), format!("{:#?}", other));
+    }
+
+    #[test]
+    fn diff_test_equal_trees() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        let mut other = Tree::new("Hello World", None);
+        other.set(0, 5, FontStyle::Bold);
+
+        assert!(tree.diff(&other).is_empty());
+    }
+
+    #[test]
+    fn diff_apply_events_test() {
+        let mut tree: Tree<FontStyle> = Tree::new("ab", None);
+        let mut other = Tree::new("abc", None);
+        other.set(0, 3, FontStyle::Bold);
+
+        let (listener, receiver) = change::channel();
+        tree.subscribe(listener);
+        tree.apply(&tree.diff(&other));
+
+        let events: Vec<OwnedEvent<FontStyle>> = receiver.try_iter().filter(|event| match event {
+            OwnedEvent::TextInserted { .. } | OwnedEvent::InfoApplied { .. } => true,
+            _ => false,
+        }).collect();
+        assert_eq!(events, vec!(
+            OwnedEvent::TextInserted { idx: 2, text: String::from("c") },
+            OwnedEvent::InfoApplied { start: 0, end: 3, info: FontStyle::Bold },
+        ));
+    }
+
+    #[test]
+    fn semantically_eq_test() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set(3, 8, FontStyle::Italic);
+        tree.set(3, 5, FontStyle::Underline);
+
+        let mut other = Tree::new("Hello World", None);
+        other.set(3, 5, FontStyle::Underline);
+        other.set(3, 8, FontStyle::Italic);
+        other.set(0, 5, FontStyle::Bold);
+
+        assert!(tree != other);
+        assert!(tree.semantically_eq(&other));
+        assert_eq!(tree.content_hash(), other.content_hash());
+
+        other.set(0, 1, FontStyle::Italic);
+        assert!(!tree.semantically_eq(&other));
+        assert_ne!(tree.content_hash(), other.content_hash());
+    }
+
+    #[test]
+    fn set_test_merge_adjacent_ranges() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set(5, 11, FontStyle::Bold);
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hello World' [Bold]
+");
+    }
+
+    #[test]
+    fn semantically_eq_test_text() {
+        let tree: Tree<FontStyle> = Tree::new("Hello World", None);
+        let other = Tree::new("Hello world", None);
+
+        assert!(!tree.semantically_eq(&other));
+        assert_ne!(tree.content_hash(), other.content_hash());
+    }
+
+    #[test]
+    fn structural_eq_test() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set(6, 11, FontStyle::Italic);
+
+        let mut other = Tree::new("Hello World", None);
+        other.set(6, 11, FontStyle::Italic);
+        other.set(0, 5, FontStyle::Bold);
+
+        assert!(tree == other);
+        assert!(tree.get_root() == other.get_root());
+        assert_eq!(tree.content_hash(), other.content_hash());
+
+        other.insert_str(11, "!");
+        assert!(tree != other);
+    }
+
+    #[test]
+    fn normalize_test_1() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set(3, 8, FontStyle::Italic);
+        tree.set(3, 5, FontStyle::Underline);
+
+        let mut other = Tree::new("Hello World", None);
+        other.set(3, 5, FontStyle::Underline);
+        other.set(3, 8, FontStyle::Italic);
+        other.set(0, 5, FontStyle::Bold);
+
+        tree.normalize();
+        other.normalize();
+
+        assert!(tree == other);
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hello World' []
+    |-- 'Hello' [Bold]
+        |-- 'Hel' []
+        |-- 'lo' [Italic, Underline]
+    |-- ' Wo' [Italic]
+    |-- 'rld' []
+");
+    }
+
+    #[test]
+    fn normalize_test_2() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(4, 7, FontStyle::Underline);
+        tree.set(6, "Hello World".len(), FontStyle::Bold);
+        tree.set(6, 9, FontStyle::Underline);
+        tree.remove(4, 2);
+        tree.unset(0, 5, FontStyle::Underline);
+        tree.set(1, 5, FontStyle::Italic);
+
+        let mut other = Tree::new("HellWorld", None);
+        other.set(4, 9, FontStyle::Bold);
+        other.set(1, 5, FontStyle::Italic);
+        other.set(5, 7, FontStyle::Underline);
+
+        tree.normalize();
+        other.normalize();
+
+        assert!(tree == other);
+        assert_eq!(format!("{:#?}", tree), "|-- 'HellWorld' []
+    |-- 'H' []
+    |-- 'ellW' [Italic]
+        |-- 'ell' []
+        |-- 'W' [Bold]
+    |-- 'orld' [Bold]
+        |-- 'or' [Underline]
+        |-- 'ld' []
+");
+    }
+
+    #[test]
+    fn normalize_test_idempotent() {
+        let events = Arc::new(Mutex::new(0));
+        let counter = Arc::clone(&events);
+        let mut tree = Tree::new("Hello World", Some(Box::new(move |_| *counter.lock().unwrap() += 1)));
+        tree.set(0, 11, FontStyle::Bold);
+        tree.set(0, 5, FontStyle::Italic);
+        tree.normalize();
+
+        let before = format!("{:#?}", tree);
+        *events.lock().unwrap() = 0;
+        tree.normalize();
+
+        assert_eq!(format!("{:#?}", tree), before);
+        assert_eq!(*events.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn validate_test_valid() {
+        let mut tree = Tree::new("Hello World", None);
+        assert!(tree.validate().is_empty());
+
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set(3, 8, FontStyle::Italic);
+        tree.remove(2, 4);
+        tree.insert_str(1, "i");
+        tree.clear(false);
+        assert!(tree.validate().is_empty());
+    }
+
+    #[test]
+    fn validate_test_invalid() {
+        let mut root: Node<FontStyle> = Node::new_root("Hello");
+        root.add_info(Shared::new(FontStyle::Bold));
+
+        let mut child = Node::new_leaf(String::from(""));
+        child.add_info(Shared::new(FontStyle::Bold));
+        let child_id = child.id();
+        root.add_child(child);
+
+        let leaf = Node::new_root("World");
+        let leaf_id = leaf.id();
+        root.add_child(leaf);
+
+        let root_id = root.id();
+        assert_eq!(root.validate(), vec!(
+            Violation::TextOnInnerNode { node_id: root_id },
+            Violation::RedundantInfo { node_id: child_id, info: Shared::new(FontStyle::Bold) },
+            Violation::EmptyLeaf { node_id: child_id },
+            Violation::MisplacedRootFlag { node_id: leaf_id },
+        ));
+    }
+
+    #[test]
+    fn validate_test_missing_text() {
+        let node: Node<FontStyle> = Node::new();
+        let id = node.id();
+
+        assert_eq!(node.validate(), vec!(
+            Violation::MissingRootFlag { node_id: id.clone() },
+            Violation::MissingText { node_id: id },
+        ));
+    }
+
+    #[test]
+    fn validate_test_unmerged_siblings() {
+        let mut root: Node<FontStyle> = Node::new_root("");
+        root.add_child(Node::new_leaf(String::from("Hello ")));
+        root.add_child(Node::new_leaf(String::from("World")));
+        root.set(6, 11, Shared::new(FontStyle::Bold));
+        let root_id = root.id();
+
+        let mut bold = Node::new_leaf(String::from("!"));
+        bold.add_info(Shared::new(FontStyle::Bold));
+        root.add_child(bold);
+
+        assert_eq!(root.validate(), vec!(
+            Violation::TextOnInnerNode { node_id: root_id.clone() },
+            Violation::UnmergedSiblings { parent_id: root_id, idx: 1 },
+        ));
+    }
+
+    #[test]
+    fn validate_test_inner_node_without_infos() {
+        let mut root: Node<FontStyle> = Node::new_root("");
+        let root_id = root.id();
+
+        let mut inner = Node::new_leaf(String::from(""));
+        inner.add_child(Node::new_leaf(String::from("Hello")));
+        let mut bold = Node::new_leaf(String::from(" "));
+        bold.add_info(Shared::new(FontStyle::Bold));
+        inner.add_child(bold);
+        let inner_id = inner.id();
+        root.add_child(inner);
+
+        let mut italic = Node::new_leaf(String::from("World"));
+        italic.add_info(Shared::new(FontStyle::Italic));
+        root.add_child(italic);
+
+        assert_eq!(root.validate(), vec!(
+            Violation::TextOnInnerNode { node_id: root_id },
+            Violation::TextOnInnerNode { node_id: inner_id.clone() },
+            Violation::InnerNodeWithoutInfos { node_id: inner_id },
+        ));
+    }
+
+    #[test]
+    fn validate_test_single_child() {
+        let mut root: Node<FontStyle> = Node::new_root("");
+        let root_id = root.id();
+
+        let mut bold = Node::new_leaf(String::from("Hello"));
+        bold.add_info(Shared::new(FontStyle::Bold));
+        root.add_child(bold);
+
+        assert_eq!(root.validate(), vec!(
+            Violation::TextOnInnerNode { node_id: root_id.clone() },
+            Violation::SingleChild { node_id: root_id },
+        ));
+    }
+
+    #[test]
+    fn validate_test_wrong_length() {
+        let mut root: Node<FontStyle> = Node::new_root("");
+        let root_id = root.id();
+
+        // Setting an info on part of a leaf without infos leaves splitting it to the parent
+        let mut leaf = Node::new_leaf(String::from("Hello"));
+        assert!(leaf.set(1, 3, Shared::new(FontStyle::Bold)).is_some());
+        let leaf_id = leaf.id();
+        root.add_child(leaf);
+
+        let mut bold = Node::new_leaf(String::from(" World"));
+        bold.add_info(Shared::new(FontStyle::Bold));
+        root.add_child(bold);
+
+        assert_eq!(root.validate(), vec!(
+            Violation::TextOnInnerNode { node_id: root_id },
+            Violation::WrongLength { node_id: leaf_id.clone(), cached: 5, actual: 0 },
+            Violation::MissingText { node_id: leaf_id },
+        ));
+    }
+
+    #[test]
+    fn set_test_enclosing_formatted_child() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(1, 2, FontStyle::Bold);
+        tree.set(0, 3, FontStyle::Bold);
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hello World' []
+    |-- 'Hel' [Bold]
+    |-- 'lo World' []
+");
+    }
+
+    #[test]
+    fn unset_test_partially_on_root() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 11, FontStyle::Bold);
+        tree.set(0, 1, FontStyle::Italic);
+        tree.unset(2, 4, FontStyle::Bold);
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hello World' []
+    |-- 'He' [Bold]
+        |-- 'H' [Italic]
+        |-- 'e' []
+    |-- 'll' []
+    |-- 'o World' [Bold]
+");
+    }
+
+    #[test]
+    fn unset_test_outside_formatted_node() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set(0, 1, FontStyle::Italic);
+        tree.unset(6, 11, FontStyle::Bold);
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hello World' []
+    |-- 'Hello' [Bold]
+        |-- 'H' [Italic]
+        |-- 'ello' []
+    |-- ' World' []
+");
+    }
+
+    #[test]
+    fn remove_test_node_merged_with_only_child() {
+        let mut root: Node<FontStyle> = Node::new_root("Hello World");
+        root.set(0, 5, Shared::new(FontStyle::Bold));
+
+        assert_eq!(root.remove(5, 6), (false, true));
+        assert_eq!(format!("{:#?}", root), "|-- 'Hello' [Bold]
+");
+        assert_eq!(root.remove(0, 2), (false, false));
+    }
+
+    #[test]
+    fn remove_test_merge_adjacent_leaves() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(4, 7, FontStyle::Bold);
+        tree.remove(4, 3);
+
+        assert_eq!(format!("{:#?}", tree), "|-- 'Hellorld' []
+");
+        assert!(tree.validate().is_empty());
+    }
+
+    fn iteration_test_tree() -> Tree<FontStyle> {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set(0, 1, FontStyle::Italic);
+        tree.set(6, 11, FontStyle::Underline);
+        tree
+    }
+
+    #[test]
+    fn post_order_iter_test() {
+        let tree = iteration_test_tree();
+
+        let items: Vec<(String, usize)> = tree.post_order_iter().map(|item| (item.node.text(), item.level)).collect();
+        assert_eq!(items, vec!(
+            (String::from("H"), 2),
+            (String::from("ello"), 2),
+            (String::from("Hello"), 1),
+            (String::from(" "), 1),
+            (String::from("World"), 1),
+            (String::from("Hello World"), 0),
+        ));
+    }
+
+    #[test]
+    fn reverse_pre_order_iter_test() {
+        let tree = iteration_test_tree();
+
+        let mut expected: Vec<(String, usize)> = tree.pre_order_iter().map(|item| (item.node.text(), item.level)).collect();
+        expected.reverse();
+
+        let items: Vec<(String, usize)> = tree.reverse_pre_order_iter().map(|item| (item.node.text(), item.level)).collect();
+        assert_eq!(items, expected);
+    }
+
+    #[test]
+    fn breadth_first_iter_test() {
+        let tree = iteration_test_tree();
+
+        let items: Vec<(String, usize)> = tree.breadth_first_iter().map(|item| (item.node.text(), item.level)).collect();
+        assert_eq!(items, vec!(
+            (String::from("Hello World"), 0),
+            (String::from("Hello"), 1),
+            (String::from(" "), 1),
+            (String::from("World"), 1),
+            (String::from("H"), 2),
+            (String::from("ello"), 2),
+        ));
+    }
+
+    #[test]
+    fn iter_test_single_leaf() {
+        let tree: Tree<FontStyle> = Tree::new("Hello", None);
+
+        assert_eq!(tree.post_order_iter().count(), 1);
+        assert_eq!(tree.reverse_pre_order_iter().count(), 1);
+        assert_eq!(tree.breadth_first_iter().count(), 1);
+    }
+
+    #[test]
+    fn iter_item_offsets_test() {
+        let tree = iteration_test_tree();
+
+        let items: Vec<(String, usize, usize, Vec<usize>)> = tree.pre_order_iter()
+            .map(|item| (item.node.text(), item.start, item.end, item.path.to_vec()))
+            .collect();
+        assert_eq!(items, vec!(
+            (String::from("Hello World"), 0, 11, vec!()),
+            (String::from("Hello"), 0, 5, vec!(0)),
+            (String::from("H"), 0, 1, vec!(0, 0)),
+            (String::from("ello"), 1, 5, vec!(0, 1)),
+            (String::from(" "), 5, 6, vec!(1)),
+            (String::from("World"), 6, 11, vec!(2)),
+        ));
+
+        for item in tree.leaf_iter() {
+            assert_eq!(&tree.text()[item.start..item.end], item.node.text());
+        }
+    }
+
+    #[test]
+    fn iter_item_offsets_test_all_orders() {
+        let tree = iteration_test_tree();
+
+        let sorted = |items: Vec<(usize, usize, Vec<usize>)>| {
+            let mut items = items;
+            items.sort();
+            items
+        };
+
+        let expected = sorted(tree.pre_order_iter().map(|item| (item.start, item.end, item.path.to_vec())).collect());
+        assert_eq!(sorted(tree.post_order_iter().map(|item| (item.start, item.end, item.path.to_vec())).collect()), expected);
+        assert_eq!(sorted(tree.reverse_pre_order_iter().map(|item| (item.start, item.end, item.path.to_vec())).collect()), expected);
+        assert_eq!(sorted(tree.breadth_first_iter().map(|item| (item.start, item.end, item.path.to_vec())).collect()), expected);
+    }
+
+    #[test]
+    fn iter_item_path_test() {
+        let tree = iteration_test_tree();
+
+        let items: Vec<_> = tree.pre_order_iter().collect();
+        assert!(items.iter().all(|item| item.path.len() == item.level));
+        assert!(items[0].path.is_empty());
+        assert_eq!(format!("{:?}", items[2].path), "[0, 0]");
+        assert_eq!(items[2].path, tree.pre_order_iter().nth(2).unwrap().path);
+        assert_ne!(items[2].path, items[3].path);
+    }
+
+    #[test]
+    fn iter_range_test() {
+        let tree = iteration_test_tree();
+
+        let items: Vec<(String, usize, usize, Vec<usize>)> = tree.iter_range(3, 8)
+            .map(|clipped| (clipped.text(), clipped.start, clipped.end, clipped.item.path.to_vec()))
+            .collect();
+        assert_eq!(items, vec!(
+            (String::from("lo Wo"), 3, 8, vec!()),
+            (String::from("lo"), 3, 5, vec!(0)),
+            (String::from("lo"), 3, 5, vec!(0, 1)),
+            (String::from(" "), 5, 6, vec!(1)),
+            (String::from("Wo"), 6, 8, vec!(2)),
+        ));
+    }
+
+    #[test]
+    fn iter_range_test_skip_subtrees() {
+        let tree = iteration_test_tree();
+
+        let items: Vec<(String, Vec<usize>)> = tree.iter_range(6, 11)
+            .map(|clipped| (clipped.text(), clipped.item.path.to_vec()))
+            .collect();
+        assert_eq!(items, vec!(
+            (String::from("World"), vec!()),
+            (String::from("World"), vec!(2)),
+        ));
+
+        let items: Vec<(String, Vec<usize>)> = tree.iter_range(0, 1)
+            .map(|clipped| (clipped.text(), clipped.item.path.to_vec()))
+            .collect();
+        assert_eq!(items, vec!(
+            (String::from("H"), vec!()),
+            (String::from("H"), vec!(0)),
+            (String::from("H"), vec!(0, 0)),
+        ));
+    }
+
+    #[test]
+    fn iter_range_test_empty_range() {
+        let tree = iteration_test_tree();
+
+        assert_eq!(tree.iter_range(5, 5).count(), 0);
+        assert_eq!(Tree::<FontStyle>::new("", None).iter_range(0, 0).count(), 0);
+    }
+
+    #[test]
+    fn iter_range_test_whole_text() {
+        let tree = iteration_test_tree();
+
+        let expected: Vec<(String, usize, usize)> = tree.pre_order_iter()
+            .map(|Item { node, level: _, start, end, path: _ }| (node.text(), start, end))
+            .collect();
+        let items: Vec<(String, usize, usize)> = tree.iter_range(0, tree.length())
+            .map(|clipped| (clipped.text(), clipped.start, clipped.end))
+            .collect();
+        assert_eq!(items, expected);
+    }
+
+    #[test]
+    fn walk_mut_test_remap_infos() {
+        let mut tree = iteration_test_tree();
+        tree.walk_mut(|node| {
+            if node.remove_info(&FontStyle::Bold) {
+                node.add_info(FontStyle::Underline);
+            }
+        });
+
+        let mut expected = Tree::new("Hello World", None);
+        expected.set(0, 5, FontStyle::Underline);
+        expected.set(0, 1, FontStyle::Italic);
+        expected.set(6, 11, FontStyle::Underline);
+
+        assert!(tree.semantically_eq(&expected));
+        assert!(tree.validate().is_empty());
+    }
+
+    #[test]
+    fn walk_mut_test_transform_text() {
+        let mut tree = iteration_test_tree();
+        tree.walk_mut(|node| {
+            if node.is_leaf() {
+                let text = node.text().to_uppercase();
+                node.set_text(text);
+            }
+        });
+
+        let mut expected = Tree::new("HELLO WORLD", None);
+        expected.set(0, 5, FontStyle::Bold);
+        expected.set(0, 1, FontStyle::Italic);
+        expected.set(6, 11, FontStyle::Underline);
+
+        assert!(tree.semantically_eq(&expected));
+        assert!(tree.validate().is_empty());
+    }
+
+    #[test]
+    fn walk_mut_test_normalize() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set(6, 11, FontStyle::Italic);
+        tree.walk_mut(|node| {
+            node.retain_infos(|info| *info != FontStyle::Italic);
+            if node.path() == [1] {
+                node.add_info(FontStyle::Bold);
+            }
+        });
+
+        let mut expected = Tree::new("Hello World", None);
+        expected.set(0, 6, FontStyle::Bold);
+        expected.normalize();
+
+        assert_eq!(tree, expected);
+    }
+
+    #[test]
+    fn walk_mut_test_events() {
+        let mut tree = iteration_test_tree();
+        let snapshot = tree.snapshot();
+
+        let (listener, receiver) = change::channel();
+        tree.subscribe(listener);
+        tree.walk_mut(|node| {
+            node.remove_info(&FontStyle::Italic);
+            if node.is_leaf() && node.text() == "World" {
+                node.set_text(String::from("Welt"));
+            }
+        });
+
+        let events: Vec<OwnedEvent<FontStyle>> = receiver.try_iter().filter(|event| match event {
+            OwnedEvent::TextInserted { .. } | OwnedEvent::TextRemoved { .. } | OwnedEvent::InfoApplied { .. } | OwnedEvent::InfoRemoved { .. } => true,
+            _ => false,
+        }).collect();
+        assert_eq!(events, vec!(
+            OwnedEvent::TextRemoved { idx: 7, len: 4 },
+            OwnedEvent::TextInserted { idx: 7, text: String::from("elt") },
+            OwnedEvent::InfoRemoved { start: 0, end: 1, info: FontStyle::Italic },
+            OwnedEvent::InfoApplied { start: 7, end: 10, info: FontStyle::Underline },
+        ));
+
+        assert_eq!(tree.text(), "Hello Welt");
+        assert_eq!(snapshot.text(), "Hello World");
+    }
+
+    #[test]
+    fn map_infos_test() {
+        let tree = iteration_test_tree();
+        let mapped: Tree<&str> = tree.map_infos(|info| match info {
+            FontStyle::Bold | FontStyle::Underline => Some("b"),
+            FontStyle::Italic => None,
+        });
+
+        let mut expected = Tree::new("Hello World", None);
+        expected.set(0, 5, "b");
+        expected.set(6, 11, "b");
+        expected.normalize();
+
+        assert_eq!(mapped, expected);
+        assert!(mapped.validate().is_empty());
+    }
+
+    #[test]
+    fn map_infos_test_merge_neighbors() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set(5, 11, FontStyle::Italic);
+
+        let mapped = tree.map_infos(|_| Some("b"));
+        assert_eq!(format!("{:#?}", mapped), "|-- 'Hello World' [\"b\"]\n");
+    }
+
+    #[test]
+    fn map_infos_test_root_infos() {
+        let mut tree = Tree::new("Hello", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.remove(0, 5);
+
+        let mut mapped = tree.map_infos(|info| Some(*info as u8));
+        mapped.push_str("Hi");
+        assert_eq!(mapped.get_root().infos_at(0).iter().map(|info| **info).collect::<Vec<u8>>(), vec!(1));
+    }
+
+    #[test]
+    fn filter_infos_test() {
+        let tree = iteration_test_tree();
+        let filtered = tree.filter_infos(|info| *info != FontStyle::Italic);
+
+        let mut expected = Tree::new("Hello World", None);
+        expected.set(0, 5, FontStyle::Bold);
+        expected.set(6, 11, FontStyle::Underline);
+        expected.normalize();
+
+        assert_eq!(filtered, expected);
+        assert_eq!(tree.text(), filtered.text());
+        assert!(tree.get_root().children()[0].children()[0].has_info(&FontStyle::Italic));
+    }
+
+    #[test]
+    fn find_node_test() {
+        let tree = iteration_test_tree();
+        let id = tree.leaf_iter().nth(1).unwrap().node.id();
+
+        assert_eq!(tree.find_node(id).unwrap().text(), "ello");
+        assert_eq!(tree.path_of(id), Some(vec!(0, 1)));
+        assert_eq!(tree.offset_of(id), Some(1));
+
+        let ancestors: Vec<(String, usize, Vec<usize>)> = tree.ancestors(id).unwrap()
+            .map(|item| (item.node.text(), item.start, item.path.to_vec()))
+            .collect();
+        assert_eq!(ancestors, vec!(
+            (String::from("Hello"), 0, vec!(0)),
+            (String::from("Hello World"), 0, vec!()),
+        ));
+    }
+
+    #[test]
+    fn find_node_test_root_and_unknown() {
+        let tree = iteration_test_tree();
+        let root_id = tree.get_root().id();
+
+        assert_eq!(tree.path_of(root_id), Some(vec!()));
+        assert_eq!(tree.offset_of(root_id), Some(0));
+        assert_eq!(tree.ancestors(root_id).unwrap().count(), 0);
+
+        let unknown = NodeId::new(u128::MAX);
+        assert!(tree.find_node(unknown).is_none());
+        assert!(tree.path_of(unknown).is_none());
+        assert!(tree.offset_of(unknown).is_none());
+        assert!(tree.ancestors(unknown).is_none());
+    }
+
+    #[test]
+    fn find_node_test_event_ids() {
+        let mut tree = iteration_test_tree();
+        let (listener, receiver) = change::channel();
+        tree.subscribe(listener);
+
+        tree.insert(8, 'x');
+
+        let node_id = receiver.try_iter().find_map(|event| match event {
+            OwnedEvent::TextChanged { node_id, .. } => Some(node_id),
+            _ => None,
+        }).unwrap();
+        assert_eq!(tree.find_node(node_id).unwrap().text(), "Woxrld");
+        assert_eq!(tree.offset_of(node_id), Some(6));
+        assert_eq!(tree.snapshot().path_of(node_id), Some(vec!(2)));
+    }
+
+    fn node_ids(tree: &Tree<FontStyle>) -> Vec<NodeId> {
+        tree.pre_order_iter().map(|item| item.node.id()).collect()
+    }
+
+    #[test]
+    fn node_id_test_deterministic() {
+        let tree = iteration_test_tree();
+        let other = iteration_test_tree();
+
+        assert_eq!(Tree::<FontStyle>::new("Hello", None).get_root().id(), NodeId::new(0));
+        assert_eq!(node_ids(&tree), node_ids(&other));
+
+        let mut ids = node_ids(&tree);
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), tree.pre_order_iter().count());
+    }
+
+    #[test]
+    fn node_id_test_detached() {
+        let tree: Tree<FontStyle> = Tree::new("Hello", None);
+        let node: Node<FontStyle> = Node::new_leaf(String::from("Hello"));
+
+        assert_ne!(node.id(), tree.get_root().id());
+        assert_eq!(node.id().value() >> 127, 1);
+        assert_eq!(node.clone().id(), node.id());
+    }
+
+    struct Stepping {
+        next: u128,
+    }
+
+    impl id::Generator for Stepping {
+        fn next_id(&mut self) -> NodeId {
+            self.next += 10;
+            NodeId::new(self.next)
+        }
+    }
+
+    #[test]
+    fn node_id_test_custom_generator() {
+        let mut tree = Tree::new("Hello World", None);
+        tree.set(0, 5, FontStyle::Bold);
+        tree.set_id_generator(Box::new(Stepping { next: 0 }));
+
+        assert_eq!(node_ids(&tree), vec!(NodeId::new(10), NodeId::new(20), NodeId::new(30)));
+
+        tree.set(6, 11, FontStyle::Italic);
+        assert!(node_ids(&tree).iter().all(|id| id.value() % 10 == 0));
+        assert_eq!(tree.find_node(NodeId::new(20)).unwrap().text(), "Hello");
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn node_id_test_uuid_generator() {
+        let mut tree = iteration_test_tree();
+        tree.set_id_generator(Box::new(id::RandomUuid));
+
+        let mut ids = node_ids(&tree);
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), tree.pre_order_iter().count());
+        assert!(ids.iter().all(|id| (id.value() >> 76) & 0xF == 4));
+    }
 }
@@ -0,0 +1,36 @@
+use proptest::prelude::*;
+use proptest::collection::vec;
+use shared::info::FontStyle;
+use shared::model::{self, Operation};
+
+fn style() -> impl Strategy<Value=FontStyle> {
+    prop_oneof![
+        Just(FontStyle::Bold),
+        Just(FontStyle::Italic),
+        Just(FontStyle::Underline),
+    ]
+}
+
+fn text() -> impl Strategy<Value=String> {
+    "[ab äx]{0,5}"
+}
+
+fn operation() -> impl Strategy<Value=Operation> {
+    prop_oneof![
+        3 => (0..32usize, 0..32usize, style()).prop_map(|(start, end, style)| Operation::Set { start, end, style }),
+        2 => (0..32usize, 0..32usize, style()).prop_map(|(start, end, style)| Operation::Unset { start, end, style }),
+        2 => (0..32usize, text()).prop_map(|(idx, text)| Operation::InsertStr { idx, text }),
+        2 => (0..32usize, 0..8usize).prop_map(|(idx, count)| Operation::Remove { idx, count }),
+        2 => text().prop_map(|text| Operation::PushStr { text }),
+        1 => any::<bool>().prop_map(|keep_formats| Operation::Clear { keep_formats }),
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+
+    #[test]
+    fn tree_matches_reference_model(operations in vec(operation(), 0..32)) {
+        model::run(&operations);
+    }
+}